@@ -10,8 +10,8 @@ use euc::{
 use minifb::{Key, Window, WindowOptions};
 use nalgebra::{Isometry3, Matrix3, Matrix4, Point3, SVector, Vector3, Vector4};
 use tinecs::{
-    Component,
-    arguments::{Query, QueryMut, With},
+    Component, Resource,
+    arguments::{Commands, Query, QueryMut, Res, ResMut, With},
     master,
 };
 
@@ -31,7 +31,7 @@ struct AngularVelocity(Vector3<f32>);
 impl Component for InertialTensor {}
 struct InertialTensor(Matrix3<f32>);
 
-impl Component for PointLight {}
+impl Resource for PointLight {}
 struct PointLight {
     pos: Vector3<f32>,
 }
@@ -42,13 +42,13 @@ struct Mesh {
     indices: Arc<Vec<usize>>,
 }
 
-impl Component for Camera {}
+impl Resource for Camera {}
 struct Camera {
     view: Matrix4<f32>,
     projection: Matrix4<f32>,
 }
 
-impl Component for RenderTarget {}
+impl Resource for RenderTarget {}
 struct RenderTarget {
     color: Buffer2d<u32>,
     depth: Buffer2d<f32>,
@@ -75,44 +75,31 @@ Systems called by ECS
 */
 
 fn integrate_body<const N: usize>(
-    integrator: QueryMut<Integrator<N>, With<DynamicBody>>,
-    dcm: QueryMut<DirectionCosine, With<DynamicBody>>,
-    vel: QueryMut<AngularVelocity, With<DynamicBody>>,
+    body: QueryMut<(&mut Integrator<N>, &mut DirectionCosine, &mut AngularVelocity), With<DynamicBody>>,
 ) {
-    let mut state = SVector::zeros();
-    for mut integrator in integrator {
-        integrator.dynamic_step();
-        state = integrator.state;
-    }
-
-    for mut dcm in dcm {
+    for (mut integrator, mut dcm, mut vel) in body {
+        let state = integrator.dynamic_step();
         *dcm = DirectionCosine(Matrix3::from_column_slice(state.rows(0, 9).as_slice()));
-    }
-    for mut vel in vel {
         *vel = AngularVelocity(Vector3::from_column_slice(state.rows(18, 3).as_slice()));
     }
 }
 
 fn render_frame(
-    camera: Query<Camera>,
-    mesh: Query<Mesh>,
-    dcm: Query<DirectionCosine>,
-    light: Query<PointLight>,
-    target: QueryMut<RenderTarget>,
+    camera: Res<Camera>,
+    meshes: Query<(&Mesh, Option<&DirectionCosine>)>,
+    light: Res<PointLight>,
+    mut target: ResMut<RenderTarget>,
 ) {
-    let camera = camera.make_singular();
-    let light = light.make_singular();
-    for mut target in target {
-        let (color, depth) = target.split_mut();
-        for (Mesh { vertices, indices }, DirectionCosine(dcm)) in mesh.into_iter().zip(&dcm) {
-            SolidUniform {
-                model: *dcm,
-                view: camera.view,
-                proj: camera.projection,
-                light: light.pos,
-            }
-            .render(IndexedVertices::new(indices.as_slice(), vertices.as_slice()), color, depth);
+    let (color, depth) = target.split_mut();
+    for (mesh, dcm) in &meshes {
+        let Mesh { vertices, indices } = &*mesh;
+        SolidUniform {
+            model: dcm.map_or(Matrix3::identity(), |dcm| dcm.0),
+            view: camera.view,
+            proj: camera.projection,
+            light: light.pos,
         }
+        .render(IndexedVertices::new(indices.as_slice(), vertices.as_slice()), color, depth);
     }
 }
 
@@ -120,16 +107,22 @@ fn render_frame(
 Entry point
 */
 
-fn main() {
-    let [width, height] = [800, 600];
+const WIDTH: usize = 800;
+const HEIGHT: usize = 600;
 
-    physics_setup();
-    graphics_setup(width, height);
+fn main() {
+    {
+        let mut ecs = master();
+        ecs.add_startup_system(physics_setup);
+        ecs.add_startup_system(graphics_setup);
+        ecs.add_system(integrate_body::<21>);
+        ecs.add_system(render_frame);
+    }
 
-    let mut window = Window::new("flippy floppy", width, height, WindowOptions::default()).unwrap();
+    let mut window = Window::new("flippy floppy", WIDTH, HEIGHT, WindowOptions::default()).unwrap();
     while window.is_open() && !window.is_key_down(Key::Escape) {
-        for mut target in master().query_mut::<RenderTarget>() {
-            window.update_with_buffer(target.color.raw(), width, height).unwrap();
+        if let Some(mut target) = master().resource_mut::<RenderTarget>() {
+            window.update_with_buffer(target.color.raw(), WIDTH, HEIGHT).unwrap();
             std::thread::sleep(std::time::Duration::from_millis(15));
             let (color, depth) = target.split_mut();
             color.clear(u32::default());
@@ -145,7 +138,7 @@ Scene setup functions (out of main for readability)
 */
 
 #[rustfmt::skip]
-fn physics_setup() {
+fn physics_setup(mut commands: Commands) {
     let dcm = DirectionCosine(Matrix3::from_row_slice(&[
         1.0, 0.0, 0.0,
         0.0, 1.0, 0.0,
@@ -219,30 +212,20 @@ fn physics_setup() {
         tolerance: 1e-6,
     };
 
-    let mut ecs = master();
-    let object = ecs.create_entity();
-    let ground = ecs.create_entity();
-    ecs.add_component(object, moi);
-    ecs.add_component(object, vel);
-    ecs.add_component(object, dcm);
-    ecs.add_component(object, mesh);
-    ecs.add_component(object, integrator);
-    ecs.add_component(object, DynamicBody);
-    ecs.add_component(ground, ground_mesh);
-    ecs.add_component(ground, DirectionCosine(Matrix3::identity()));
-    ecs.add_system(integrate_body::<21>);
+    commands.spawn((moi, vel, dcm, mesh, integrator, DynamicBody));
+    commands.spawn(ground_mesh);
 }
 
-fn graphics_setup(width: usize, height: usize) {
+fn graphics_setup(mut commands: Commands) {
     let render_target = RenderTarget {
-        color: Buffer2d::fill([width, height], u32::default()),
-        depth: Buffer2d::fill([width, height], f32::MAX),
+        color: Buffer2d::fill([WIDTH, HEIGHT], u32::default()),
+        depth: Buffer2d::fill([WIDTH, HEIGHT], f32::MAX),
     };
     let camera = Camera {
         view: Isometry3::look_at_rh(&Point3::new(0.0, 5.0, -12.5), &Point3::origin(), &Vector3::y())
             .to_matrix(),
         projection: Matrix4::new_perspective(
-            (width as f32) / (height as f32),
+            (WIDTH as f32) / (HEIGHT as f32),
             90.0_f32.to_radians(),
             0.1,
             100.0,
@@ -250,14 +233,9 @@ fn graphics_setup(width: usize, height: usize) {
     };
     let point_light = PointLight { pos: Vector3::new(-3.0, 8.0, -7.0) };
 
-    let mut ecs = master();
-    let renderer = ecs.create_entity();
-    let viewer = ecs.create_entity();
-    let light = ecs.create_entity();
-    ecs.add_component(renderer, render_target);
-    ecs.add_component(viewer, camera);
-    ecs.add_component(light, point_light);
-    ecs.add_system(render_frame);
+    commands.insert_resource(render_target);
+    commands.insert_resource(camera);
+    commands.insert_resource(point_light);
 }
 
 /*
@@ -17,15 +17,17 @@ fn main() {
     master.add_component(person1, NameTag { name: "john".to_string() });
     master.add_component(person2, NameTag { name: "quan".to_string() });
 
-    master.add_system(greetings);
-    master.add_system(greet_people);
+    let greeter = master.add_system(greetings);
+    let people_greeter = master.add_system(greet_people);
     master.run();
 
-    master.remove_system(greetings);
-    master.remove_system(greet_people);
-    master.add_system(change_names);
+    master.remove_system(greeter);
+    master.remove_system(people_greeter);
+    let renamer = master.add_system(change_names);
     master.run();
 
+    master.remove_system(renamer);
+
     master.add_system(greetings);
     master.add_system(greet_people);
     master.run();
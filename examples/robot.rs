@@ -1,9 +1,13 @@
+use std::time::Duration;
+
 use nalgebra::Vector2;
 use rand::random_range;
 use tinecs::{
     Component,
-    arguments::{Query, QueryMut, With},
+    arguments::{Query, QueryMut, Res, With},
     master,
+    schedule::{Stage, SystemGroup},
+    time::{FixedTime, Time},
 };
 
 impl Component for Robot {}
@@ -16,7 +20,7 @@ impl Component for Velocity {}
 struct Velocity(Vector2<isize>);
 
 fn move_robot(pos: QueryMut<Position, With<Robot>>, vel: Query<Velocity, With<Robot>>) {
-    let Velocity(vel) = vel.make_singular();
+    let Velocity(vel) = *vel.make_singular();
     for mut pos in pos {
         let Position(old_pos) = *pos;
         *pos = Position(old_pos + vel);
@@ -29,21 +33,17 @@ fn control_robot(vel: QueryMut<Velocity, With<Robot>>) {
     }
 }
 
-fn report_robot(robot: Query<Position, With<Robot>>) {
-    let Position(pos) = robot.make_singular();
-    println!("robot is at {{{:?}}}", pos);
+fn report_robot(robot: Query<Position, With<Robot>>, time: Res<Time>) {
+    let Position(pos) = *robot.make_singular();
+    println!("robot is at {{{:?}}} after {:.1}s", pos, time.elapsed_secs());
 }
 
 fn main() {
     let mut ecs = master();
-    let robot = ecs.create_entity();
-    ecs.add_component(robot, Robot);
-    ecs.add_component(robot, Position(Vector2::zeros()));
-    ecs.add_component(robot, Velocity(Vector2::zeros()));
-
-    ecs.add_system(move_robot);
-    ecs.add_system(control_robot);
-    ecs.add_system(report_robot);
+    ecs.spawn((Robot, Position(Vector2::zeros()), Velocity(Vector2::zeros())));
+
+    ecs.insert_resource(FixedTime::new(Duration::from_millis(100)));
+    ecs.add_systems_to_stage(Stage::FixedUpdate, (move_robot, control_robot, report_robot).chain());
 
     loop {
         ecs.run();
@@ -1,192 +1,2272 @@
 use std::{
-    any::Any,
-    cell::{Ref, RefMut},
+    any::{TypeId, type_name},
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
     marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::{
+        RwLockReadGuard, RwLockWriteGuard,
+        atomic::{AtomicU64, Ordering},
+    },
 };
 
-use crate::{Component, ComponentMap, Entity, systems::SystemArg};
+use crate::{
+    Component, Entity, Resource, StorageType, World,
+    bundle::Bundle,
+    storage::{Archetype, ComponentColumn, ComponentMap, Slot, Ticks},
+    systems::{Access, ReadOnlySystemArg, SystemArg},
+};
 
 pub trait QueryFilter {
-    fn matches(components: &ComponentMap, entity: Entity) -> bool;
+    type Columns<'d>;
+
+    fn matches_archetype(_: &Archetype) -> bool {
+        true
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d>;
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool;
+}
+
+impl QueryFilter for () {
+    type Columns<'d> = ();
+
+    fn prepare<'d>(_: &'d ComponentMap, _: &'d Archetype) -> Self::Columns<'d> {}
+
+    fn matches(_: &Self::Columns<'_>, _: usize, _: Ticks) -> bool {
+        true
+    }
+}
+
+pub struct With<T>(PhantomData<T>);
+
+impl<T> QueryFilter for With<T>
+where
+    T: Component,
+{
+    type Columns<'d> = ComponentColumn<'d, T>;
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        T::STORAGE == StorageType::SparseSet || archetype.contains(TypeId::of::<T>())
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        ComponentColumn::new(components, archetype)
+    }
+
+    fn matches(column: &Self::Columns<'_>, row: usize, _: Ticks) -> bool {
+        column.get(row).is_some()
+    }
+}
+
+fn has<T>(components: &ComponentMap, archetype: &Archetype, row: usize) -> bool
+where
+    T: Component,
+{
+    match T::STORAGE {
+        | StorageType::Table => archetype.contains(TypeId::of::<T>()),
+        | StorageType::SparseSet => components.has_sparse::<T>(archetype.entities()[row]),
+    }
+}
+
+pub struct Without<T>(PhantomData<T>);
+
+impl<T> QueryFilter for Without<T>
+where
+    T: Component,
+{
+    type Columns<'d> = ComponentColumn<'d, T>;
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        T::STORAGE == StorageType::SparseSet || !archetype.contains(TypeId::of::<T>())
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        ComponentColumn::new(components, archetype)
+    }
+
+    fn matches(column: &Self::Columns<'_>, row: usize, _: Ticks) -> bool {
+        column.get(row).is_none()
+    }
+}
+
+pub struct Added<T>(PhantomData<T>);
+
+impl<T> QueryFilter for Added<T>
+where
+    T: Component,
+{
+    type Columns<'d> = ComponentColumn<'d, T>;
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        With::<T>::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        ComponentColumn::new(components, archetype)
+    }
+
+    fn matches(column: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        column.get(row).is_some_and(|slot| ticks.is_newer(slot.added()))
+    }
+}
+
+pub struct Changed<T>(PhantomData<T>);
+
+impl<T> QueryFilter for Changed<T>
+where
+    T: Component,
+{
+    type Columns<'d> = ComponentColumn<'d, T>;
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        With::<T>::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        ComponentColumn::new(components, archetype)
+    }
+
+    fn matches(column: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        column.get(row).is_some_and(|slot| ticks.is_newer(slot.changed()))
+    }
+}
+
+pub struct Not<F>(PhantomData<F>);
+
+impl<F> QueryFilter for Not<F>
+where
+    F: QueryFilter,
+{
+    type Columns<'d> = (bool, F::Columns<'d>);
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (F::matches_archetype(archetype), F::prepare(components, archetype))
+    }
+
+    fn matches((matched, columns): &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        !(*matched && F::matches(columns, row, ticks))
+    }
+}
+
+pub struct Or<F>(PhantomData<F>);
+
+impl<A> QueryFilter for (A,)
+where
+    A: QueryFilter,
+{
+    type Columns<'d> = (A::Columns<'d>,);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (A::prepare(components, archetype),)
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        A::matches(&columns.0, row, ticks)
+    }
+}
+
+impl<A, B> QueryFilter for (A, B)
+where
+    A: QueryFilter,
+    B: QueryFilter,
+{
+    type Columns<'d> = (A::Columns<'d>, B::Columns<'d>);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype) && B::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (A::prepare(components, archetype), B::prepare(components, archetype))
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        A::matches(&columns.0, row, ticks) && B::matches(&columns.1, row, ticks)
+    }
+}
+
+impl<A, B, C> QueryFilter for (A, B, C)
+where
+    A: QueryFilter,
+    B: QueryFilter,
+    C: QueryFilter,
+{
+    type Columns<'d> = (A::Columns<'d>, B::Columns<'d>, C::Columns<'d>);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype) && B::matches_archetype(archetype) && C::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            A::prepare(components, archetype),
+            B::prepare(components, archetype),
+            C::prepare(components, archetype),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        A::matches(&columns.0, row, ticks)
+            && B::matches(&columns.1, row, ticks)
+            && C::matches(&columns.2, row, ticks)
+    }
+}
+
+impl<A, B, C, D> QueryFilter for (A, B, C, D)
+where
+    A: QueryFilter,
+    B: QueryFilter,
+    C: QueryFilter,
+    D: QueryFilter,
+{
+    type Columns<'d> = (A::Columns<'d>, B::Columns<'d>, C::Columns<'d>, D::Columns<'d>);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            && B::matches_archetype(archetype)
+            && C::matches_archetype(archetype)
+            && D::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            A::prepare(components, archetype),
+            B::prepare(components, archetype),
+            C::prepare(components, archetype),
+            D::prepare(components, archetype),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        A::matches(&columns.0, row, ticks)
+            && B::matches(&columns.1, row, ticks)
+            && C::matches(&columns.2, row, ticks)
+            && D::matches(&columns.3, row, ticks)
+    }
+}
+
+impl<A, B, C, D, E> QueryFilter for (A, B, C, D, E)
+where
+    A: QueryFilter,
+    B: QueryFilter,
+    C: QueryFilter,
+    D: QueryFilter,
+    E: QueryFilter,
+{
+    type Columns<'d> = (A::Columns<'d>, B::Columns<'d>, C::Columns<'d>, D::Columns<'d>, E::Columns<'d>);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            && B::matches_archetype(archetype)
+            && C::matches_archetype(archetype)
+            && D::matches_archetype(archetype)
+            && E::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            A::prepare(components, archetype),
+            B::prepare(components, archetype),
+            C::prepare(components, archetype),
+            D::prepare(components, archetype),
+            E::prepare(components, archetype),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        A::matches(&columns.0, row, ticks)
+            && B::matches(&columns.1, row, ticks)
+            && C::matches(&columns.2, row, ticks)
+            && D::matches(&columns.3, row, ticks)
+            && E::matches(&columns.4, row, ticks)
+    }
+}
+
+impl<A, B, C, D, E, G> QueryFilter for (A, B, C, D, E, G)
+where
+    A: QueryFilter,
+    B: QueryFilter,
+    C: QueryFilter,
+    D: QueryFilter,
+    E: QueryFilter,
+    G: QueryFilter,
+{
+    type Columns<'d> =
+        (A::Columns<'d>, B::Columns<'d>, C::Columns<'d>, D::Columns<'d>, E::Columns<'d>, G::Columns<'d>);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            && B::matches_archetype(archetype)
+            && C::matches_archetype(archetype)
+            && D::matches_archetype(archetype)
+            && E::matches_archetype(archetype)
+            && G::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            A::prepare(components, archetype),
+            B::prepare(components, archetype),
+            C::prepare(components, archetype),
+            D::prepare(components, archetype),
+            E::prepare(components, archetype),
+            G::prepare(components, archetype),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        A::matches(&columns.0, row, ticks)
+            && B::matches(&columns.1, row, ticks)
+            && C::matches(&columns.2, row, ticks)
+            && D::matches(&columns.3, row, ticks)
+            && E::matches(&columns.4, row, ticks)
+            && G::matches(&columns.5, row, ticks)
+    }
+}
+
+impl<A, B, C, D, E, G, H> QueryFilter for (A, B, C, D, E, G, H)
+where
+    A: QueryFilter,
+    B: QueryFilter,
+    C: QueryFilter,
+    D: QueryFilter,
+    E: QueryFilter,
+    G: QueryFilter,
+    H: QueryFilter,
+{
+    type Columns<'d> = (
+        A::Columns<'d>,
+        B::Columns<'d>,
+        C::Columns<'d>,
+        D::Columns<'d>,
+        E::Columns<'d>,
+        G::Columns<'d>,
+        H::Columns<'d>,
+    );
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            && B::matches_archetype(archetype)
+            && C::matches_archetype(archetype)
+            && D::matches_archetype(archetype)
+            && E::matches_archetype(archetype)
+            && G::matches_archetype(archetype)
+            && H::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            A::prepare(components, archetype),
+            B::prepare(components, archetype),
+            C::prepare(components, archetype),
+            D::prepare(components, archetype),
+            E::prepare(components, archetype),
+            G::prepare(components, archetype),
+            H::prepare(components, archetype),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        A::matches(&columns.0, row, ticks)
+            && B::matches(&columns.1, row, ticks)
+            && C::matches(&columns.2, row, ticks)
+            && D::matches(&columns.3, row, ticks)
+            && E::matches(&columns.4, row, ticks)
+            && G::matches(&columns.5, row, ticks)
+            && H::matches(&columns.6, row, ticks)
+    }
+}
+
+impl<A, B, C, D, E, G, H, I> QueryFilter for (A, B, C, D, E, G, H, I)
+where
+    A: QueryFilter,
+    B: QueryFilter,
+    C: QueryFilter,
+    D: QueryFilter,
+    E: QueryFilter,
+    G: QueryFilter,
+    H: QueryFilter,
+    I: QueryFilter,
+{
+    type Columns<'d> = (
+        A::Columns<'d>,
+        B::Columns<'d>,
+        C::Columns<'d>,
+        D::Columns<'d>,
+        E::Columns<'d>,
+        G::Columns<'d>,
+        H::Columns<'d>,
+        I::Columns<'d>,
+    );
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            && B::matches_archetype(archetype)
+            && C::matches_archetype(archetype)
+            && D::matches_archetype(archetype)
+            && E::matches_archetype(archetype)
+            && G::matches_archetype(archetype)
+            && H::matches_archetype(archetype)
+            && I::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            A::prepare(components, archetype),
+            B::prepare(components, archetype),
+            C::prepare(components, archetype),
+            D::prepare(components, archetype),
+            E::prepare(components, archetype),
+            G::prepare(components, archetype),
+            H::prepare(components, archetype),
+            I::prepare(components, archetype),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        A::matches(&columns.0, row, ticks)
+            && B::matches(&columns.1, row, ticks)
+            && C::matches(&columns.2, row, ticks)
+            && D::matches(&columns.3, row, ticks)
+            && E::matches(&columns.4, row, ticks)
+            && G::matches(&columns.5, row, ticks)
+            && H::matches(&columns.6, row, ticks)
+            && I::matches(&columns.7, row, ticks)
+    }
+}
+
+impl<A> QueryFilter for Or<(A,)>
+where
+    A: QueryFilter,
+{
+    type Columns<'d> = ((bool, A::Columns<'d>),);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        ((A::matches_archetype(archetype), A::prepare(components, archetype)),)
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        columns.0.0 && A::matches(&columns.0.1, row, ticks)
+    }
+}
+
+impl<A, B> QueryFilter for Or<(A, B)>
+where
+    A: QueryFilter,
+    B: QueryFilter,
+{
+    type Columns<'d> = ((bool, A::Columns<'d>), (bool, B::Columns<'d>));
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype) || B::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            (A::matches_archetype(archetype), A::prepare(components, archetype)),
+            (B::matches_archetype(archetype), B::prepare(components, archetype)),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        (columns.0.0 && A::matches(&columns.0.1, row, ticks))
+            || (columns.1.0 && B::matches(&columns.1.1, row, ticks))
+    }
+}
+
+impl<A, B, C> QueryFilter for Or<(A, B, C)>
+where
+    A: QueryFilter,
+    B: QueryFilter,
+    C: QueryFilter,
+{
+    type Columns<'d> = ((bool, A::Columns<'d>), (bool, B::Columns<'d>), (bool, C::Columns<'d>));
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype) || B::matches_archetype(archetype) || C::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            (A::matches_archetype(archetype), A::prepare(components, archetype)),
+            (B::matches_archetype(archetype), B::prepare(components, archetype)),
+            (C::matches_archetype(archetype), C::prepare(components, archetype)),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        (columns.0.0 && A::matches(&columns.0.1, row, ticks))
+            || (columns.1.0 && B::matches(&columns.1.1, row, ticks))
+            || (columns.2.0 && C::matches(&columns.2.1, row, ticks))
+    }
+}
+
+impl<A, B, C, D> QueryFilter for Or<(A, B, C, D)>
+where
+    A: QueryFilter,
+    B: QueryFilter,
+    C: QueryFilter,
+    D: QueryFilter,
+{
+    type Columns<'d> =
+        ((bool, A::Columns<'d>), (bool, B::Columns<'d>), (bool, C::Columns<'d>), (bool, D::Columns<'d>));
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            || B::matches_archetype(archetype)
+            || C::matches_archetype(archetype)
+            || D::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            (A::matches_archetype(archetype), A::prepare(components, archetype)),
+            (B::matches_archetype(archetype), B::prepare(components, archetype)),
+            (C::matches_archetype(archetype), C::prepare(components, archetype)),
+            (D::matches_archetype(archetype), D::prepare(components, archetype)),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        (columns.0.0 && A::matches(&columns.0.1, row, ticks))
+            || (columns.1.0 && B::matches(&columns.1.1, row, ticks))
+            || (columns.2.0 && C::matches(&columns.2.1, row, ticks))
+            || (columns.3.0 && D::matches(&columns.3.1, row, ticks))
+    }
+}
+
+impl<A, B, C, D, E> QueryFilter for Or<(A, B, C, D, E)>
+where
+    A: QueryFilter,
+    B: QueryFilter,
+    C: QueryFilter,
+    D: QueryFilter,
+    E: QueryFilter,
+{
+    type Columns<'d> = (
+        (bool, A::Columns<'d>),
+        (bool, B::Columns<'d>),
+        (bool, C::Columns<'d>),
+        (bool, D::Columns<'d>),
+        (bool, E::Columns<'d>),
+    );
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            || B::matches_archetype(archetype)
+            || C::matches_archetype(archetype)
+            || D::matches_archetype(archetype)
+            || E::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            (A::matches_archetype(archetype), A::prepare(components, archetype)),
+            (B::matches_archetype(archetype), B::prepare(components, archetype)),
+            (C::matches_archetype(archetype), C::prepare(components, archetype)),
+            (D::matches_archetype(archetype), D::prepare(components, archetype)),
+            (E::matches_archetype(archetype), E::prepare(components, archetype)),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        (columns.0.0 && A::matches(&columns.0.1, row, ticks))
+            || (columns.1.0 && B::matches(&columns.1.1, row, ticks))
+            || (columns.2.0 && C::matches(&columns.2.1, row, ticks))
+            || (columns.3.0 && D::matches(&columns.3.1, row, ticks))
+            || (columns.4.0 && E::matches(&columns.4.1, row, ticks))
+    }
+}
+
+impl<A, B, C, D, E, G> QueryFilter for Or<(A, B, C, D, E, G)>
+where
+    A: QueryFilter,
+    B: QueryFilter,
+    C: QueryFilter,
+    D: QueryFilter,
+    E: QueryFilter,
+    G: QueryFilter,
+{
+    type Columns<'d> = (
+        (bool, A::Columns<'d>),
+        (bool, B::Columns<'d>),
+        (bool, C::Columns<'d>),
+        (bool, D::Columns<'d>),
+        (bool, E::Columns<'d>),
+        (bool, G::Columns<'d>),
+    );
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            || B::matches_archetype(archetype)
+            || C::matches_archetype(archetype)
+            || D::matches_archetype(archetype)
+            || E::matches_archetype(archetype)
+            || G::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            (A::matches_archetype(archetype), A::prepare(components, archetype)),
+            (B::matches_archetype(archetype), B::prepare(components, archetype)),
+            (C::matches_archetype(archetype), C::prepare(components, archetype)),
+            (D::matches_archetype(archetype), D::prepare(components, archetype)),
+            (E::matches_archetype(archetype), E::prepare(components, archetype)),
+            (G::matches_archetype(archetype), G::prepare(components, archetype)),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        (columns.0.0 && A::matches(&columns.0.1, row, ticks))
+            || (columns.1.0 && B::matches(&columns.1.1, row, ticks))
+            || (columns.2.0 && C::matches(&columns.2.1, row, ticks))
+            || (columns.3.0 && D::matches(&columns.3.1, row, ticks))
+            || (columns.4.0 && E::matches(&columns.4.1, row, ticks))
+            || (columns.5.0 && G::matches(&columns.5.1, row, ticks))
+    }
+}
+
+impl<A, B, C, D, E, G, H> QueryFilter for Or<(A, B, C, D, E, G, H)>
+where
+    A: QueryFilter,
+    B: QueryFilter,
+    C: QueryFilter,
+    D: QueryFilter,
+    E: QueryFilter,
+    G: QueryFilter,
+    H: QueryFilter,
+{
+    type Columns<'d> = (
+        (bool, A::Columns<'d>),
+        (bool, B::Columns<'d>),
+        (bool, C::Columns<'d>),
+        (bool, D::Columns<'d>),
+        (bool, E::Columns<'d>),
+        (bool, G::Columns<'d>),
+        (bool, H::Columns<'d>),
+    );
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            || B::matches_archetype(archetype)
+            || C::matches_archetype(archetype)
+            || D::matches_archetype(archetype)
+            || E::matches_archetype(archetype)
+            || G::matches_archetype(archetype)
+            || H::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            (A::matches_archetype(archetype), A::prepare(components, archetype)),
+            (B::matches_archetype(archetype), B::prepare(components, archetype)),
+            (C::matches_archetype(archetype), C::prepare(components, archetype)),
+            (D::matches_archetype(archetype), D::prepare(components, archetype)),
+            (E::matches_archetype(archetype), E::prepare(components, archetype)),
+            (G::matches_archetype(archetype), G::prepare(components, archetype)),
+            (H::matches_archetype(archetype), H::prepare(components, archetype)),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        (columns.0.0 && A::matches(&columns.0.1, row, ticks))
+            || (columns.1.0 && B::matches(&columns.1.1, row, ticks))
+            || (columns.2.0 && C::matches(&columns.2.1, row, ticks))
+            || (columns.3.0 && D::matches(&columns.3.1, row, ticks))
+            || (columns.4.0 && E::matches(&columns.4.1, row, ticks))
+            || (columns.5.0 && G::matches(&columns.5.1, row, ticks))
+            || (columns.6.0 && H::matches(&columns.6.1, row, ticks))
+    }
+}
+
+impl<A, B, C, D, E, G, H, I> QueryFilter for Or<(A, B, C, D, E, G, H, I)>
+where
+    A: QueryFilter,
+    B: QueryFilter,
+    C: QueryFilter,
+    D: QueryFilter,
+    E: QueryFilter,
+    G: QueryFilter,
+    H: QueryFilter,
+    I: QueryFilter,
+{
+    type Columns<'d> = (
+        (bool, A::Columns<'d>),
+        (bool, B::Columns<'d>),
+        (bool, C::Columns<'d>),
+        (bool, D::Columns<'d>),
+        (bool, E::Columns<'d>),
+        (bool, G::Columns<'d>),
+        (bool, H::Columns<'d>),
+        (bool, I::Columns<'d>),
+    );
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            || B::matches_archetype(archetype)
+            || C::matches_archetype(archetype)
+            || D::matches_archetype(archetype)
+            || E::matches_archetype(archetype)
+            || G::matches_archetype(archetype)
+            || H::matches_archetype(archetype)
+            || I::matches_archetype(archetype)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype) -> Self::Columns<'d> {
+        (
+            (A::matches_archetype(archetype), A::prepare(components, archetype)),
+            (B::matches_archetype(archetype), B::prepare(components, archetype)),
+            (C::matches_archetype(archetype), C::prepare(components, archetype)),
+            (D::matches_archetype(archetype), D::prepare(components, archetype)),
+            (E::matches_archetype(archetype), E::prepare(components, archetype)),
+            (G::matches_archetype(archetype), G::prepare(components, archetype)),
+            (H::matches_archetype(archetype), H::prepare(components, archetype)),
+            (I::matches_archetype(archetype), I::prepare(components, archetype)),
+        )
+    }
+
+    fn matches(columns: &Self::Columns<'_>, row: usize, ticks: Ticks) -> bool {
+        (columns.0.0 && A::matches(&columns.0.1, row, ticks))
+            || (columns.1.0 && B::matches(&columns.1.1, row, ticks))
+            || (columns.2.0 && C::matches(&columns.2.1, row, ticks))
+            || (columns.3.0 && D::matches(&columns.3.1, row, ticks))
+            || (columns.4.0 && E::matches(&columns.4.1, row, ticks))
+            || (columns.5.0 && G::matches(&columns.5.1, row, ticks))
+            || (columns.6.0 && H::matches(&columns.6.1, row, ticks))
+            || (columns.7.0 && I::matches(&columns.7.1, row, ticks))
+    }
+}
+
+pub trait QueryFetch {
+    type Item<'d>;
+
+    type Columns<'d>;
+
+    type ReadOnly: QueryFetch;
+
+    fn matches_archetype(archetype: &Archetype) -> bool;
+
+    fn missing(components: &ComponentMap, archetype: &Archetype, row: usize) -> Option<&'static str>;
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype, ticks: Ticks)
+    -> Self::Columns<'d>;
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>>;
+
+    fn access(access: &mut Access);
+}
+
+pub type QueryItem<'d, Q> = <Q as QueryFetch>::Item<'d>;
+
+impl<'a, T> QueryFetch for &'a T
+where
+    T: Component,
+{
+    type Item<'d> = RwLockReadGuard<'d, T>;
+
+    type Columns<'d> = ComponentColumn<'d, T>;
+
+    type ReadOnly = &'a T;
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        With::<T>::matches_archetype(archetype)
+    }
+
+    fn missing(components: &ComponentMap, archetype: &Archetype, row: usize) -> Option<&'static str> {
+        (!has::<T>(components, archetype, row)).then(type_name::<T>)
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype, _: Ticks) -> Self::Columns<'d> {
+        ComponentColumn::new(components, archetype)
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        columns.get(row).map(Slot::borrow)
+    }
+
+    fn access(access: &mut Access) {
+        access.read_component::<T>();
+    }
+}
+
+impl<'a, T> QueryFetch for &'a mut T
+where
+    T: Component,
+{
+    type Item<'d> = Mut<'d, T>;
+
+    type Columns<'d> = (ComponentColumn<'d, T>, u64);
+
+    type ReadOnly = &'a T;
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        With::<T>::matches_archetype(archetype)
+    }
+
+    fn missing(components: &ComponentMap, archetype: &Archetype, row: usize) -> Option<&'static str> {
+        (!has::<T>(components, archetype, row)).then(type_name::<T>)
+    }
+
+    fn prepare<'d>(
+        components: &'d ComponentMap,
+        archetype: &'d Archetype,
+        ticks: Ticks,
+    ) -> Self::Columns<'d> {
+        (ComponentColumn::new(components, archetype), ticks.this_run())
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        let (column, tick) = columns;
+        column.get(row).map(|slot| slot.borrow_tracked(*tick))
+    }
+
+    fn access(access: &mut Access) {
+        access.write_component::<T>();
+    }
+}
+
+impl<'a, T> QueryFetch for Option<&'a T>
+where
+    T: Component,
+{
+    type Item<'d> = Option<RwLockReadGuard<'d, T>>;
+
+    type Columns<'d> = ComponentColumn<'d, T>;
+
+    type ReadOnly = Option<&'a T>;
+
+    fn matches_archetype(_: &Archetype) -> bool {
+        true
+    }
+
+    fn missing(_: &ComponentMap, _: &Archetype, _: usize) -> Option<&'static str> {
+        None
+    }
+
+    fn prepare<'d>(components: &'d ComponentMap, archetype: &'d Archetype, _: Ticks) -> Self::Columns<'d> {
+        ComponentColumn::new(components, archetype)
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        Some(columns.get(row).map(Slot::borrow))
+    }
+
+    fn access(access: &mut Access) {
+        access.read_component::<T>();
+    }
+}
+
+impl<'a, T> QueryFetch for Option<&'a mut T>
+where
+    T: Component,
+{
+    type Item<'d> = Option<Mut<'d, T>>;
+
+    type Columns<'d> = (ComponentColumn<'d, T>, u64);
+
+    type ReadOnly = Option<&'a T>;
+
+    fn matches_archetype(_: &Archetype) -> bool {
+        true
+    }
+
+    fn missing(_: &ComponentMap, _: &Archetype, _: usize) -> Option<&'static str> {
+        None
+    }
+
+    fn prepare<'d>(
+        components: &'d ComponentMap,
+        archetype: &'d Archetype,
+        ticks: Ticks,
+    ) -> Self::Columns<'d> {
+        (ComponentColumn::new(components, archetype), ticks.this_run())
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        let (column, tick) = columns;
+        Some(column.get(row).map(|slot| slot.borrow_tracked(*tick)))
+    }
+
+    fn access(access: &mut Access) {
+        access.write_component::<T>();
+    }
+}
+
+impl QueryFetch for Entity {
+    type Item<'d> = Entity;
+
+    type Columns<'d> = &'d [Entity];
+
+    type ReadOnly = Self;
+
+    fn matches_archetype(_: &Archetype) -> bool {
+        true
+    }
+
+    fn missing(_: &ComponentMap, _: &Archetype, _: usize) -> Option<&'static str> {
+        None
+    }
+
+    fn prepare<'d>(_: &'d ComponentMap, archetype: &'d Archetype, _: Ticks) -> Self::Columns<'d> {
+        archetype.entities()
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        Some(columns[row])
+    }
+
+    fn access(_: &mut Access) {}
+}
+
+/// What a `Query` or `QueryMut` fetches. A single component is named bare, as in
+/// `Query<Position>` or `QueryMut<Position>`; several are a tuple of references, as in
+/// `QueryMut<(&mut Position, &Velocity, Option<&Mass>, Entity)>`. A bare `&Position` is not
+/// query data, since it would overlap with the component impl; write `Position` or `(&Position,)`.
+pub trait QueryData {
+    type Read: QueryFetch;
+
+    type Write: QueryFetch;
+}
+
+impl<T> QueryData for T
+where
+    T: Component,
+{
+    type Read = &'static T;
+
+    type Write = &'static mut T;
+}
+
+impl QueryData for Entity {
+    type Read = Self;
+
+    type Write = Self;
+}
+
+/// Query data that never hands out write access, so `Query<T>` can back a run condition.
+///
+/// ```compile_fail
+/// use tinecs::{Component, Master, arguments::Query, systems::SystemBuilder};
+///
+/// struct Position(i32);
+/// impl Component for Position {}
+///
+/// fn update() {}
+/// fn mutating(query: Query<(&mut Position,)>) -> bool {
+///     query.iter().next().is_some()
+/// }
+///
+/// Master::default().add_system(update.run_if(mutating));
+/// ```
+pub trait ReadOnlyQueryData: QueryData {}
+
+impl<T> ReadOnlyQueryData for T where T: Component {}
+
+impl ReadOnlyQueryData for Entity {}
+
+impl<A> QueryFetch for (A,)
+where
+    A: QueryFetch,
+{
+    type Item<'d> = (A::Item<'d>,);
+
+    type Columns<'d> = (A::Columns<'d>,);
+
+    type ReadOnly = (A::ReadOnly,);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+    }
+
+    fn missing(components: &ComponentMap, archetype: &Archetype, row: usize) -> Option<&'static str> {
+        A::missing(components, archetype, row)
+    }
+
+    fn prepare<'d>(
+        components: &'d ComponentMap,
+        archetype: &'d Archetype,
+        ticks: Ticks,
+    ) -> Self::Columns<'d> {
+        (A::prepare(components, archetype, ticks),)
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        Some((A::fetch(&columns.0, row)?,))
+    }
+
+    fn access(access: &mut Access) {
+        A::access(access);
+    }
+}
+
+impl<A> QueryData for (A,)
+where
+    A: QueryFetch,
+{
+    type Read = (A::ReadOnly,);
+
+    type Write = Self;
+}
+
+impl<A> ReadOnlyQueryData for (A,) where A: QueryFetch<ReadOnly = A> {}
+
+impl<A, B> QueryFetch for (A, B)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+{
+    type Item<'d> = (A::Item<'d>, B::Item<'d>);
+
+    type Columns<'d> = (A::Columns<'d>, B::Columns<'d>);
+
+    type ReadOnly = (A::ReadOnly, B::ReadOnly);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype) && B::matches_archetype(archetype)
+    }
+
+    fn missing(components: &ComponentMap, archetype: &Archetype, row: usize) -> Option<&'static str> {
+        A::missing(components, archetype, row).or_else(|| B::missing(components, archetype, row))
+    }
+
+    fn prepare<'d>(
+        components: &'d ComponentMap,
+        archetype: &'d Archetype,
+        ticks: Ticks,
+    ) -> Self::Columns<'d> {
+        (A::prepare(components, archetype, ticks), B::prepare(components, archetype, ticks))
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        Some((A::fetch(&columns.0, row)?, B::fetch(&columns.1, row)?))
+    }
+
+    fn access(access: &mut Access) {
+        A::access(access);
+        B::access(access);
+    }
+}
+
+impl<A, B> QueryData for (A, B)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+{
+    type Read = (A::ReadOnly, B::ReadOnly);
+
+    type Write = Self;
+}
+
+impl<A, B> ReadOnlyQueryData for (A, B)
+where
+    A: QueryFetch<ReadOnly = A>,
+    B: QueryFetch<ReadOnly = B>,
+{
+}
+
+impl<A, B, C> QueryFetch for (A, B, C)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+    C: QueryFetch,
+{
+    type Item<'d> = (A::Item<'d>, B::Item<'d>, C::Item<'d>);
+
+    type Columns<'d> = (A::Columns<'d>, B::Columns<'d>, C::Columns<'d>);
+
+    type ReadOnly = (A::ReadOnly, B::ReadOnly, C::ReadOnly);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype) && B::matches_archetype(archetype) && C::matches_archetype(archetype)
+    }
+
+    fn missing(components: &ComponentMap, archetype: &Archetype, row: usize) -> Option<&'static str> {
+        A::missing(components, archetype, row)
+            .or_else(|| B::missing(components, archetype, row))
+            .or_else(|| C::missing(components, archetype, row))
+    }
+
+    fn prepare<'d>(
+        components: &'d ComponentMap,
+        archetype: &'d Archetype,
+        ticks: Ticks,
+    ) -> Self::Columns<'d> {
+        (
+            A::prepare(components, archetype, ticks),
+            B::prepare(components, archetype, ticks),
+            C::prepare(components, archetype, ticks),
+        )
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        Some((A::fetch(&columns.0, row)?, B::fetch(&columns.1, row)?, C::fetch(&columns.2, row)?))
+    }
+
+    fn access(access: &mut Access) {
+        A::access(access);
+        B::access(access);
+        C::access(access);
+    }
+}
+
+impl<A, B, C> QueryData for (A, B, C)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+    C: QueryFetch,
+{
+    type Read = (A::ReadOnly, B::ReadOnly, C::ReadOnly);
+
+    type Write = Self;
+}
+
+impl<A, B, C> ReadOnlyQueryData for (A, B, C)
+where
+    A: QueryFetch<ReadOnly = A>,
+    B: QueryFetch<ReadOnly = B>,
+    C: QueryFetch<ReadOnly = C>,
+{
+}
+
+impl<A, B, C, D> QueryFetch for (A, B, C, D)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+    C: QueryFetch,
+    D: QueryFetch,
+{
+    type Item<'d> = (A::Item<'d>, B::Item<'d>, C::Item<'d>, D::Item<'d>);
+
+    type Columns<'d> = (A::Columns<'d>, B::Columns<'d>, C::Columns<'d>, D::Columns<'d>);
+
+    type ReadOnly = (A::ReadOnly, B::ReadOnly, C::ReadOnly, D::ReadOnly);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            && B::matches_archetype(archetype)
+            && C::matches_archetype(archetype)
+            && D::matches_archetype(archetype)
+    }
+
+    fn missing(components: &ComponentMap, archetype: &Archetype, row: usize) -> Option<&'static str> {
+        A::missing(components, archetype, row)
+            .or_else(|| B::missing(components, archetype, row))
+            .or_else(|| C::missing(components, archetype, row))
+            .or_else(|| D::missing(components, archetype, row))
+    }
+
+    fn prepare<'d>(
+        components: &'d ComponentMap,
+        archetype: &'d Archetype,
+        ticks: Ticks,
+    ) -> Self::Columns<'d> {
+        (
+            A::prepare(components, archetype, ticks),
+            B::prepare(components, archetype, ticks),
+            C::prepare(components, archetype, ticks),
+            D::prepare(components, archetype, ticks),
+        )
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        Some((
+            A::fetch(&columns.0, row)?,
+            B::fetch(&columns.1, row)?,
+            C::fetch(&columns.2, row)?,
+            D::fetch(&columns.3, row)?,
+        ))
+    }
+
+    fn access(access: &mut Access) {
+        A::access(access);
+        B::access(access);
+        C::access(access);
+        D::access(access);
+    }
+}
+
+impl<A, B, C, D> QueryData for (A, B, C, D)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+    C: QueryFetch,
+    D: QueryFetch,
+{
+    type Read = (A::ReadOnly, B::ReadOnly, C::ReadOnly, D::ReadOnly);
+
+    type Write = Self;
+}
+
+impl<A, B, C, D> ReadOnlyQueryData for (A, B, C, D)
+where
+    A: QueryFetch<ReadOnly = A>,
+    B: QueryFetch<ReadOnly = B>,
+    C: QueryFetch<ReadOnly = C>,
+    D: QueryFetch<ReadOnly = D>,
+{
+}
+
+impl<A, B, C, D, E> QueryFetch for (A, B, C, D, E)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+    C: QueryFetch,
+    D: QueryFetch,
+    E: QueryFetch,
+{
+    type Item<'d> = (A::Item<'d>, B::Item<'d>, C::Item<'d>, D::Item<'d>, E::Item<'d>);
+
+    type Columns<'d> = (A::Columns<'d>, B::Columns<'d>, C::Columns<'d>, D::Columns<'d>, E::Columns<'d>);
+
+    type ReadOnly = (A::ReadOnly, B::ReadOnly, C::ReadOnly, D::ReadOnly, E::ReadOnly);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            && B::matches_archetype(archetype)
+            && C::matches_archetype(archetype)
+            && D::matches_archetype(archetype)
+            && E::matches_archetype(archetype)
+    }
+
+    fn missing(components: &ComponentMap, archetype: &Archetype, row: usize) -> Option<&'static str> {
+        A::missing(components, archetype, row)
+            .or_else(|| B::missing(components, archetype, row))
+            .or_else(|| C::missing(components, archetype, row))
+            .or_else(|| D::missing(components, archetype, row))
+            .or_else(|| E::missing(components, archetype, row))
+    }
+
+    fn prepare<'d>(
+        components: &'d ComponentMap,
+        archetype: &'d Archetype,
+        ticks: Ticks,
+    ) -> Self::Columns<'d> {
+        (
+            A::prepare(components, archetype, ticks),
+            B::prepare(components, archetype, ticks),
+            C::prepare(components, archetype, ticks),
+            D::prepare(components, archetype, ticks),
+            E::prepare(components, archetype, ticks),
+        )
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        Some((
+            A::fetch(&columns.0, row)?,
+            B::fetch(&columns.1, row)?,
+            C::fetch(&columns.2, row)?,
+            D::fetch(&columns.3, row)?,
+            E::fetch(&columns.4, row)?,
+        ))
+    }
+
+    fn access(access: &mut Access) {
+        A::access(access);
+        B::access(access);
+        C::access(access);
+        D::access(access);
+        E::access(access);
+    }
+}
+
+impl<A, B, C, D, E> QueryData for (A, B, C, D, E)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+    C: QueryFetch,
+    D: QueryFetch,
+    E: QueryFetch,
+{
+    type Read = (A::ReadOnly, B::ReadOnly, C::ReadOnly, D::ReadOnly, E::ReadOnly);
+
+    type Write = Self;
+}
+
+impl<A, B, C, D, E> ReadOnlyQueryData for (A, B, C, D, E)
+where
+    A: QueryFetch<ReadOnly = A>,
+    B: QueryFetch<ReadOnly = B>,
+    C: QueryFetch<ReadOnly = C>,
+    D: QueryFetch<ReadOnly = D>,
+    E: QueryFetch<ReadOnly = E>,
+{
+}
+
+impl<A, B, C, D, E, G> QueryFetch for (A, B, C, D, E, G)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+    C: QueryFetch,
+    D: QueryFetch,
+    E: QueryFetch,
+    G: QueryFetch,
+{
+    type Item<'d> = (A::Item<'d>, B::Item<'d>, C::Item<'d>, D::Item<'d>, E::Item<'d>, G::Item<'d>);
+
+    type Columns<'d> =
+        (A::Columns<'d>, B::Columns<'d>, C::Columns<'d>, D::Columns<'d>, E::Columns<'d>, G::Columns<'d>);
+
+    type ReadOnly = (A::ReadOnly, B::ReadOnly, C::ReadOnly, D::ReadOnly, E::ReadOnly, G::ReadOnly);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            && B::matches_archetype(archetype)
+            && C::matches_archetype(archetype)
+            && D::matches_archetype(archetype)
+            && E::matches_archetype(archetype)
+            && G::matches_archetype(archetype)
+    }
+
+    fn missing(components: &ComponentMap, archetype: &Archetype, row: usize) -> Option<&'static str> {
+        A::missing(components, archetype, row)
+            .or_else(|| B::missing(components, archetype, row))
+            .or_else(|| C::missing(components, archetype, row))
+            .or_else(|| D::missing(components, archetype, row))
+            .or_else(|| E::missing(components, archetype, row))
+            .or_else(|| G::missing(components, archetype, row))
+    }
+
+    fn prepare<'d>(
+        components: &'d ComponentMap,
+        archetype: &'d Archetype,
+        ticks: Ticks,
+    ) -> Self::Columns<'d> {
+        (
+            A::prepare(components, archetype, ticks),
+            B::prepare(components, archetype, ticks),
+            C::prepare(components, archetype, ticks),
+            D::prepare(components, archetype, ticks),
+            E::prepare(components, archetype, ticks),
+            G::prepare(components, archetype, ticks),
+        )
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        Some((
+            A::fetch(&columns.0, row)?,
+            B::fetch(&columns.1, row)?,
+            C::fetch(&columns.2, row)?,
+            D::fetch(&columns.3, row)?,
+            E::fetch(&columns.4, row)?,
+            G::fetch(&columns.5, row)?,
+        ))
+    }
+
+    fn access(access: &mut Access) {
+        A::access(access);
+        B::access(access);
+        C::access(access);
+        D::access(access);
+        E::access(access);
+        G::access(access);
+    }
+}
+
+impl<A, B, C, D, E, G> QueryData for (A, B, C, D, E, G)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+    C: QueryFetch,
+    D: QueryFetch,
+    E: QueryFetch,
+    G: QueryFetch,
+{
+    type Read = (A::ReadOnly, B::ReadOnly, C::ReadOnly, D::ReadOnly, E::ReadOnly, G::ReadOnly);
+
+    type Write = Self;
+}
+
+impl<A, B, C, D, E, G> ReadOnlyQueryData for (A, B, C, D, E, G)
+where
+    A: QueryFetch<ReadOnly = A>,
+    B: QueryFetch<ReadOnly = B>,
+    C: QueryFetch<ReadOnly = C>,
+    D: QueryFetch<ReadOnly = D>,
+    E: QueryFetch<ReadOnly = E>,
+    G: QueryFetch<ReadOnly = G>,
+{
 }
 
-impl QueryFilter for () {
-    fn matches(_: &ComponentMap, _: Entity) -> bool {
-        true
+impl<A, B, C, D, E, G, H> QueryFetch for (A, B, C, D, E, G, H)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+    C: QueryFetch,
+    D: QueryFetch,
+    E: QueryFetch,
+    G: QueryFetch,
+    H: QueryFetch,
+{
+    type Item<'d> =
+        (A::Item<'d>, B::Item<'d>, C::Item<'d>, D::Item<'d>, E::Item<'d>, G::Item<'d>, H::Item<'d>);
+
+    type Columns<'d> = (
+        A::Columns<'d>,
+        B::Columns<'d>,
+        C::Columns<'d>,
+        D::Columns<'d>,
+        E::Columns<'d>,
+        G::Columns<'d>,
+        H::Columns<'d>,
+    );
+
+    type ReadOnly =
+        (A::ReadOnly, B::ReadOnly, C::ReadOnly, D::ReadOnly, E::ReadOnly, G::ReadOnly, H::ReadOnly);
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            && B::matches_archetype(archetype)
+            && C::matches_archetype(archetype)
+            && D::matches_archetype(archetype)
+            && E::matches_archetype(archetype)
+            && G::matches_archetype(archetype)
+            && H::matches_archetype(archetype)
+    }
+
+    fn missing(components: &ComponentMap, archetype: &Archetype, row: usize) -> Option<&'static str> {
+        A::missing(components, archetype, row)
+            .or_else(|| B::missing(components, archetype, row))
+            .or_else(|| C::missing(components, archetype, row))
+            .or_else(|| D::missing(components, archetype, row))
+            .or_else(|| E::missing(components, archetype, row))
+            .or_else(|| G::missing(components, archetype, row))
+            .or_else(|| H::missing(components, archetype, row))
+    }
+
+    fn prepare<'d>(
+        components: &'d ComponentMap,
+        archetype: &'d Archetype,
+        ticks: Ticks,
+    ) -> Self::Columns<'d> {
+        (
+            A::prepare(components, archetype, ticks),
+            B::prepare(components, archetype, ticks),
+            C::prepare(components, archetype, ticks),
+            D::prepare(components, archetype, ticks),
+            E::prepare(components, archetype, ticks),
+            G::prepare(components, archetype, ticks),
+            H::prepare(components, archetype, ticks),
+        )
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        Some((
+            A::fetch(&columns.0, row)?,
+            B::fetch(&columns.1, row)?,
+            C::fetch(&columns.2, row)?,
+            D::fetch(&columns.3, row)?,
+            E::fetch(&columns.4, row)?,
+            G::fetch(&columns.5, row)?,
+            H::fetch(&columns.6, row)?,
+        ))
+    }
+
+    fn access(access: &mut Access) {
+        A::access(access);
+        B::access(access);
+        C::access(access);
+        D::access(access);
+        E::access(access);
+        G::access(access);
+        H::access(access);
     }
 }
 
-pub struct With<T>(PhantomData<T>);
+impl<A, B, C, D, E, G, H> QueryData for (A, B, C, D, E, G, H)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+    C: QueryFetch,
+    D: QueryFetch,
+    E: QueryFetch,
+    G: QueryFetch,
+    H: QueryFetch,
+{
+    type Read = (A::ReadOnly, B::ReadOnly, C::ReadOnly, D::ReadOnly, E::ReadOnly, G::ReadOnly, H::ReadOnly);
 
-impl<T> QueryFilter for With<T>
+    type Write = Self;
+}
+
+impl<A, B, C, D, E, G, H> ReadOnlyQueryData for (A, B, C, D, E, G, H)
+where
+    A: QueryFetch<ReadOnly = A>,
+    B: QueryFetch<ReadOnly = B>,
+    C: QueryFetch<ReadOnly = C>,
+    D: QueryFetch<ReadOnly = D>,
+    E: QueryFetch<ReadOnly = E>,
+    G: QueryFetch<ReadOnly = G>,
+    H: QueryFetch<ReadOnly = H>,
+{
+}
+
+impl<A, B, C, D, E, G, H, I> QueryFetch for (A, B, C, D, E, G, H, I)
 where
-    T: 'static,
+    A: QueryFetch,
+    B: QueryFetch,
+    C: QueryFetch,
+    D: QueryFetch,
+    E: QueryFetch,
+    G: QueryFetch,
+    H: QueryFetch,
+    I: QueryFetch,
 {
-    fn matches(components: &ComponentMap, entity: Entity) -> bool {
-        components.has_component::<T>(entity)
+    type Item<'d> = (
+        A::Item<'d>,
+        B::Item<'d>,
+        C::Item<'d>,
+        D::Item<'d>,
+        E::Item<'d>,
+        G::Item<'d>,
+        H::Item<'d>,
+        I::Item<'d>,
+    );
+
+    type Columns<'d> = (
+        A::Columns<'d>,
+        B::Columns<'d>,
+        C::Columns<'d>,
+        D::Columns<'d>,
+        E::Columns<'d>,
+        G::Columns<'d>,
+        H::Columns<'d>,
+        I::Columns<'d>,
+    );
+
+    type ReadOnly = (
+        A::ReadOnly,
+        B::ReadOnly,
+        C::ReadOnly,
+        D::ReadOnly,
+        E::ReadOnly,
+        G::ReadOnly,
+        H::ReadOnly,
+        I::ReadOnly,
+    );
+
+    fn matches_archetype(archetype: &Archetype) -> bool {
+        A::matches_archetype(archetype)
+            && B::matches_archetype(archetype)
+            && C::matches_archetype(archetype)
+            && D::matches_archetype(archetype)
+            && E::matches_archetype(archetype)
+            && G::matches_archetype(archetype)
+            && H::matches_archetype(archetype)
+            && I::matches_archetype(archetype)
+    }
+
+    fn missing(components: &ComponentMap, archetype: &Archetype, row: usize) -> Option<&'static str> {
+        A::missing(components, archetype, row)
+            .or_else(|| B::missing(components, archetype, row))
+            .or_else(|| C::missing(components, archetype, row))
+            .or_else(|| D::missing(components, archetype, row))
+            .or_else(|| E::missing(components, archetype, row))
+            .or_else(|| G::missing(components, archetype, row))
+            .or_else(|| H::missing(components, archetype, row))
+            .or_else(|| I::missing(components, archetype, row))
+    }
+
+    fn prepare<'d>(
+        components: &'d ComponentMap,
+        archetype: &'d Archetype,
+        ticks: Ticks,
+    ) -> Self::Columns<'d> {
+        (
+            A::prepare(components, archetype, ticks),
+            B::prepare(components, archetype, ticks),
+            C::prepare(components, archetype, ticks),
+            D::prepare(components, archetype, ticks),
+            E::prepare(components, archetype, ticks),
+            G::prepare(components, archetype, ticks),
+            H::prepare(components, archetype, ticks),
+            I::prepare(components, archetype, ticks),
+        )
+    }
+
+    fn fetch<'d>(columns: &Self::Columns<'d>, row: usize) -> Option<Self::Item<'d>> {
+        Some((
+            A::fetch(&columns.0, row)?,
+            B::fetch(&columns.1, row)?,
+            C::fetch(&columns.2, row)?,
+            D::fetch(&columns.3, row)?,
+            E::fetch(&columns.4, row)?,
+            G::fetch(&columns.5, row)?,
+            H::fetch(&columns.6, row)?,
+            I::fetch(&columns.7, row)?,
+        ))
+    }
+
+    fn access(access: &mut Access) {
+        A::access(access);
+        B::access(access);
+        C::access(access);
+        D::access(access);
+        E::access(access);
+        G::access(access);
+        H::access(access);
+        I::access(access);
     }
 }
 
-pub struct Without<T>(PhantomData<T>);
+impl<A, B, C, D, E, G, H, I> QueryData for (A, B, C, D, E, G, H, I)
+where
+    A: QueryFetch,
+    B: QueryFetch,
+    C: QueryFetch,
+    D: QueryFetch,
+    E: QueryFetch,
+    G: QueryFetch,
+    H: QueryFetch,
+    I: QueryFetch,
+{
+    type Read = (
+        A::ReadOnly,
+        B::ReadOnly,
+        C::ReadOnly,
+        D::ReadOnly,
+        E::ReadOnly,
+        G::ReadOnly,
+        H::ReadOnly,
+        I::ReadOnly,
+    );
 
-impl<T> QueryFilter for Without<T>
+    type Write = Self;
+}
+
+impl<A, B, C, D, E, G, H, I> ReadOnlyQueryData for (A, B, C, D, E, G, H, I)
 where
-    T: 'static,
+    A: QueryFetch<ReadOnly = A>,
+    B: QueryFetch<ReadOnly = B>,
+    C: QueryFetch<ReadOnly = C>,
+    D: QueryFetch<ReadOnly = D>,
+    E: QueryFetch<ReadOnly = E>,
+    G: QueryFetch<ReadOnly = G>,
+    H: QueryFetch<ReadOnly = H>,
+    I: QueryFetch<ReadOnly = I>,
 {
-    fn matches(components: &ComponentMap, entity: Entity) -> bool {
-        !components.has_component::<T>(entity)
-    }
 }
 
-pub(crate) struct InnerCluster<'d> {
-    pub(crate) entity: Entity,
-    pub(crate) component: Ref<'d, Box<dyn Component>>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryError {
+    NoSuchEntity(Entity),
+    MissingComponent { entity: Entity, component: &'static str },
+    Filtered(Entity),
+    AliasedMutability(Entity),
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            | QueryError::NoSuchEntity(entity) => {
+                write!(f, "entity {} does not exist", **entity)
+            }
+            | QueryError::MissingComponent { entity, component } => {
+                write!(f, "entity {} has no component {}", **entity, component)
+            }
+            | QueryError::Filtered(entity) => {
+                write!(f, "entity {} is rejected by the query filter", **entity)
+            }
+            | QueryError::AliasedMutability(entity) => {
+                write!(f, "entity {} was requested mutably more than once", **entity)
+            }
+        }
+    }
 }
 
+impl Error for QueryError {}
+
 pub struct Query<'d, T, F = ()> {
-    pub(crate) inner: Vec<InnerCluster<'d>>,
+    pub(crate) components: &'d ComponentMap,
+    pub(crate) ticks: Ticks,
     pub(crate) marker: PhantomData<&'d T>,
     pub(crate) fmarker: PhantomData<F>,
 }
 
 impl<'d, T, F> Query<'d, T, F>
 where
-    T: Component + Any + 'static,
+    T: QueryData,
     F: QueryFilter,
 {
-    pub fn make_singular(&'d self) -> &'d T {
-        let mut iter = self.inner.iter().filter_map(|cluster| cluster.component.as_any().downcast_ref());
-        let first = iter.next();
-        if first.is_none() {
-            panic!("can't be made singular: null");
-        }
-        if iter.next().is_some() {
-            panic!("can't be made singular: non-singular");
-        }
-        first.unwrap()
+    pub(crate) fn new(components: &'d ComponentMap, ticks: Ticks) -> Self {
+        Query { components, ticks, marker: PhantomData, fmarker: PhantomData }
+    }
+
+    pub fn iter(&self) -> QueryIter<'d, T::Read, F> {
+        QueryIter::new(self.components, self.ticks)
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.components.check_entity::<T::Read, F>(entity, self.ticks).is_ok()
+    }
+
+    pub fn get(&self, entity: Entity) -> Result<QueryItem<'d, T::Read>, QueryError> {
+        let (archetype, row) = self.components.check_entity::<T::Read, F>(entity, self.ticks)?;
+        Ok(T::Read::fetch(&T::Read::prepare(self.components, archetype, self.ticks), row).unwrap())
     }
-}
 
-pub struct QueryIter<'d, T> {
-    inner: std::vec::IntoIter<&'d T>,
-    marker: PhantomData<&'d T>,
+    pub fn make_singular(&self) -> QueryItem<'d, T::Read> {
+        singular(self.iter())
+    }
 }
 
-impl<'d, T, F> IntoIterator for &'d Query<'d, T, F>
+fn singular<I>(mut iter: I) -> I::Item
 where
-    T: Component + Any + 'static,
+    I: Iterator,
 {
-    type Item = &'d T;
+    let Some(item) = iter.next() else {
+        panic!("can't be made singular: null");
+    };
+    if iter.next().is_some() {
+        panic!("can't be made singular: non-singular");
+    }
+    item
+}
 
-    type IntoIter = QueryIter<'d, T>;
+pub struct QueryIter<'d, Q, F = ()>
+where
+    Q: QueryFetch,
+    F: QueryFilter,
+{
+    components: &'d ComponentMap,
+    ticks: Ticks,
+    archetypes: std::slice::Iter<'d, Archetype>,
+    current: Option<(&'d Archetype, Q::Columns<'d>, F::Columns<'d>)>,
+    row: usize,
+}
 
-    fn into_iter(self) -> Self::IntoIter {
+impl<'d, Q, F> QueryIter<'d, Q, F>
+where
+    Q: QueryFetch,
+    F: QueryFilter,
+{
+    fn new(components: &'d ComponentMap, ticks: Ticks) -> Self {
         QueryIter {
-            inner: self
-                .inner
-                .iter()
-                .filter_map(|cluster| cluster.component.as_any().downcast_ref())
-                .collect::<Vec<&'d T>>()
-                .into_iter(),
-            marker: PhantomData,
+            components,
+            ticks,
+            archetypes: components.archetypes().iter(),
+            current: None,
+            row: 0,
         }
     }
 }
 
-impl<'d, T> Iterator for QueryIter<'d, T> {
-    type Item = &'d T;
+impl<'d, Q, F> Iterator for QueryIter<'d, Q, F>
+where
+    Q: QueryFetch,
+    F: QueryFilter,
+{
+    type Item = QueryItem<'d, Q>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next()
+        loop {
+            if let Some((archetype, columns, filter)) = &self.current {
+                while self.row < archetype.len() {
+                    let row = self.row;
+                    self.row += 1;
+                    if !F::matches(filter, row, self.ticks) {
+                        continue;
+                    }
+                    if let Some(item) = Q::fetch(columns, row) {
+                        return Some(item);
+                    }
+                }
+            }
+            let archetype = self
+                .archetypes
+                .find(|archetype| Q::matches_archetype(archetype) && F::matches_archetype(archetype))?;
+            self.current = Some((
+                archetype,
+                Q::prepare(self.components, archetype, self.ticks),
+                F::prepare(self.components, archetype),
+            ));
+            self.row = 0;
+        }
+    }
+}
+
+impl<'d, T, F> IntoIterator for &Query<'d, T, F>
+where
+    T: QueryData,
+    F: QueryFilter,
+{
+    type Item = QueryItem<'d, T::Read>;
+
+    type IntoIter = QueryIter<'d, T::Read, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'d, T, F> IntoIterator for Query<'d, T, F>
+where
+    T: QueryData,
+    F: QueryFilter,
+{
+    type Item = QueryItem<'d, T::Read>;
+
+    type IntoIter = QueryIter<'d, T::Read, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        QueryIter::new(self.components, self.ticks)
     }
 }
 
 impl<'d, T, F> SystemArg for Query<'d, T, F>
 where
-    T: 'static,
+    T: QueryData + 'static,
     F: QueryFilter,
 {
     type Item<'o> = Query<'o, T, F>;
 
-    fn fetch<'i>(components: &'i ComponentMap) -> Self::Item<'i> {
-        components.query_components_filtered()
+    type State = ();
+
+    fn fetch<'i>(world: &'i World, _: &'i mut Self::State, ticks: Ticks) -> Self::Item<'i> {
+        Query::new(&world.components, ticks)
+    }
+
+    fn access(access: &mut Access) {
+        T::Read::access(access);
     }
 }
 
-pub(crate) struct InnerClusterMut<'d> {
-    pub(crate) entity: Entity,
-    pub(crate) component: RefMut<'d, Box<dyn Component>>,
+impl<'d, T, F> ReadOnlySystemArg for Query<'d, T, F>
+where
+    T: ReadOnlyQueryData + 'static,
+    F: QueryFilter,
+{
 }
 
+/// Items borrow the query, so two live `get_mut` results for one entity are rejected at compile time.
+///
+/// ```compile_fail
+/// use tinecs::{Component, Master};
+///
+/// struct Position(i32);
+/// impl Component for Position {}
+///
+/// let mut master = Master::default();
+/// let entity = master.spawn(Position(0));
+/// let mut query = master.query_mut::<Position>();
+/// let first = query.get_mut(entity).unwrap();
+/// let second = query.get_mut(entity).unwrap();
+/// drop((first, second));
+/// ```
 pub struct QueryMut<'d, T, F = ()> {
-    pub(crate) inner: Vec<InnerClusterMut<'d>>,
+    pub(crate) components: &'d ComponentMap,
+    pub(crate) ticks: Ticks,
     pub(crate) marker: PhantomData<&'d mut T>,
     pub(crate) fmarker: PhantomData<F>,
 }
 
 impl<'d, T, F> QueryMut<'d, T, F>
 where
-    T: Component + Any + 'static,
+    T: QueryData,
     F: QueryFilter,
 {
-    pub fn make_singular_mut(&'d mut self) -> RefMut<'d, T> {
-        if self.inner.is_empty() {
-            panic!("can't be made singular: null");
-        }
-        if self.inner.len() > 1 {
-            panic!("can't be made singular: non-singular");
+    pub(crate) fn new(components: &'d ComponentMap, ticks: Ticks) -> Self {
+        QueryMut { components, ticks, marker: PhantomData, fmarker: PhantomData }
+    }
+
+    pub fn iter(&self) -> QueryIter<'_, T::Read, F> {
+        QueryIter::new(self.components, self.ticks)
+    }
+
+    pub fn iter_mut(&mut self) -> QueryIter<'_, T::Write, F> {
+        QueryIter::new(self.components, self.ticks)
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.components.check_entity::<T::Write, F>(entity, self.ticks).is_ok()
+    }
+
+    pub fn get(&self, entity: Entity) -> Result<QueryItem<'_, T::Read>, QueryError> {
+        let (archetype, row) = self.components.check_entity::<T::Write, F>(entity, self.ticks)?;
+        Ok(T::Read::fetch(&T::Read::prepare(self.components, archetype, self.ticks), row).unwrap())
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Result<QueryItem<'_, T::Write>, QueryError> {
+        let (archetype, row) = self.components.check_entity::<T::Write, F>(entity, self.ticks)?;
+        Ok(T::Write::fetch(&T::Write::prepare(self.components, archetype, self.ticks), row).unwrap())
+    }
+
+    pub fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [Entity; N],
+    ) -> Result<[QueryItem<'_, T::Write>; N], QueryError> {
+        let mut locations = [(&self.components.archetypes()[0], 0); N];
+        for (idx, entity) in entities.iter().enumerate() {
+            locations[idx] = self.components.check_entity::<T::Write, F>(*entity, self.ticks)?;
+            if entities[..idx].contains(entity) {
+                return Err(QueryError::AliasedMutability(*entity));
+            }
         }
-        let single = self.inner.pop().unwrap();
-        RefMut::map(single.component, |value| value.as_any_mut().downcast_mut().unwrap())
+        Ok(locations.map(|(archetype, row)| {
+            T::Write::fetch(&T::Write::prepare(self.components, archetype, self.ticks), row).unwrap()
+        }))
     }
-}
 
-pub struct QueryIterMut<'d, T, F> {
-    inner: std::vec::IntoIter<InnerClusterMut<'d>>,
-    marker: PhantomData<&'d mut T>,
-    fmarker: PhantomData<F>,
+    pub fn make_singular_mut(&mut self) -> QueryItem<'_, T::Write> {
+        singular(self.iter_mut())
+    }
 }
 
 impl<'d, T, F> IntoIterator for QueryMut<'d, T, F>
 where
-    T: 'static,
+    T: QueryData,
     F: QueryFilter,
 {
-    type Item = RefMut<'d, T>;
+    type Item = QueryItem<'d, T::Write>;
 
-    type IntoIter = QueryIterMut<'d, T, F>;
+    type IntoIter = QueryIter<'d, T::Write, F>;
 
     fn into_iter(self) -> Self::IntoIter {
-        QueryIterMut {
-            inner: self.inner.into_iter(),
-            marker: PhantomData,
-            fmarker: PhantomData,
-        }
+        QueryIter::new(self.components, self.ticks)
     }
 }
 
-impl<'d, T, F> Iterator for QueryIterMut<'d, T, F>
+impl<'d, T, F> SystemArg for QueryMut<'d, T, F>
 where
-    T: 'static,
+    T: QueryData + 'static,
     F: QueryFilter,
 {
-    type Item = RefMut<'d, T>;
+    type Item<'o> = QueryMut<'o, T, F>;
+
+    type State = ();
+
+    fn fetch<'i>(world: &'i World, _: &'i mut Self::State, ticks: Ticks) -> Self::Item<'i> {
+        QueryMut::new(&world.components, ticks)
+    }
+
+    fn access(access: &mut Access) {
+        T::Write::access(access);
+    }
+}
+
+pub struct RemovedComponents<'d, T> {
+    removed: &'d [(Entity, u64)],
+    ticks: Ticks,
+    marker: PhantomData<T>,
+}
+
+impl<'d, T> RemovedComponents<'d, T> {
+    pub fn iter(&self) -> RemovedIter<'d> {
+        RemovedIter { removed: self.removed.iter(), ticks: self.ticks }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+impl<'d, T> IntoIterator for RemovedComponents<'d, T> {
+    type Item = Entity;
+
+    type IntoIter = RemovedIter<'d>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'d, T> IntoIterator for &RemovedComponents<'d, T> {
+    type Item = Entity;
+
+    type IntoIter = RemovedIter<'d>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct RemovedIter<'d> {
+    removed: std::slice::Iter<'d, (Entity, u64)>,
+    ticks: Ticks,
+}
+
+impl Iterator for RemovedIter<'_> {
+    type Item = Entity;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Some(RefMut::map(self.inner.next()?.component, |value| {
-            value.as_any_mut().downcast_mut().unwrap()
-        }))
+        self.removed.find(|(_, tick)| self.ticks.is_newer(*tick)).map(|(entity, _)| *entity)
     }
 }
 
-impl<'d, T, F> SystemArg for QueryMut<'d, T, F>
+impl<'d, T> SystemArg for RemovedComponents<'d, T>
 where
-    T: 'static,
-    F: QueryFilter,
+    T: Component,
 {
-    type Item<'o> = QueryMut<'o, T, F>;
+    type Item<'o> = RemovedComponents<'o, T>;
+
+    type State = ();
+
+    fn fetch<'i>(world: &'i World, _: &'i mut Self::State, ticks: Ticks) -> Self::Item<'i> {
+        RemovedComponents {
+            removed: world.components.removed::<T>(),
+            ticks,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'d, T> ReadOnlySystemArg for RemovedComponents<'d, T> where T: Component {}
+
+pub struct Res<'d, T> {
+    pub(crate) inner: RwLockReadGuard<'d, T>,
+}
+
+impl<T> Deref for Res<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'d, T> SystemArg for Res<'d, T>
+where
+    T: Resource,
+{
+    type Item<'o> = Res<'o, T>;
+
+    type State = ();
+
+    fn fetch<'i>(world: &'i World, _: &'i mut Self::State, _: Ticks) -> Self::Item<'i> {
+        world.resource().unwrap_or_else(|| panic!("resource {} does not exist", type_name::<T>()))
+    }
+
+    fn access(access: &mut Access) {
+        access.read_resource::<T>();
+    }
+}
+
+impl<'d, T> ReadOnlySystemArg for Res<'d, T> where T: Resource {}
+
+impl<'d, T> SystemArg for Option<Res<'d, T>>
+where
+    T: Resource,
+{
+    type Item<'o> = Option<Res<'o, T>>;
+
+    type State = ();
+
+    fn fetch<'i>(world: &'i World, _: &'i mut Self::State, _: Ticks) -> Self::Item<'i> {
+        world.resource()
+    }
+
+    fn access(access: &mut Access) {
+        access.read_resource::<T>();
+    }
+}
+
+impl<'d, T> ReadOnlySystemArg for Option<Res<'d, T>> where T: Resource {}
+
+pub struct ResMut<'d, T> {
+    pub(crate) inner: RwLockWriteGuard<'d, T>,
+}
+
+impl<T> Deref for ResMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for ResMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+pub struct Mut<'d, T> {
+    pub(crate) inner: RwLockWriteGuard<'d, T>,
+    pub(crate) changed: &'d AtomicU64,
+    pub(crate) tick: u64,
+}
+
+impl<T> Deref for Mut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for Mut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.changed.store(self.tick, Ordering::Relaxed);
+        &mut self.inner
+    }
+}
+
+impl<'d, T> SystemArg for ResMut<'d, T>
+where
+    T: Resource,
+{
+    type Item<'o> = ResMut<'o, T>;
+
+    type State = ();
+
+    fn fetch<'i>(world: &'i World, _: &'i mut Self::State, _: Ticks) -> Self::Item<'i> {
+        world.resource_mut().unwrap_or_else(|| panic!("resource {} does not exist", type_name::<T>()))
+    }
+
+    fn access(access: &mut Access) {
+        access.write_resource::<T>();
+    }
+}
+
+impl<'d, T> SystemArg for Option<ResMut<'d, T>>
+where
+    T: Resource,
+{
+    type Item<'o> = Option<ResMut<'o, T>>;
+
+    type State = ();
+
+    fn fetch<'i>(world: &'i World, _: &'i mut Self::State, _: Ticks) -> Self::Item<'i> {
+        world.resource_mut()
+    }
+
+    fn access(access: &mut Access) {
+        access.write_resource::<T>();
+    }
+}
+
+type Command = Box<dyn FnOnce(&mut World) + Send + Sync>;
+
+#[derive(Default)]
+pub struct CommandQueue {
+    commands: Vec<Command>,
+}
+
+impl CommandQueue {
+    pub fn push<C>(&mut self, command: C)
+    where
+        C: FnOnce(&mut World) + Send + Sync + 'static,
+    {
+        self.commands.push(Box::new(command));
+    }
+
+    pub fn apply(&mut self, world: &mut World) {
+        for command in self.commands.drain(..) {
+            command(world);
+        }
+    }
+}
+
+pub struct Commands<'d> {
+    world: &'d World,
+    queue: &'d mut CommandQueue,
+}
+
+impl Commands<'_> {
+    pub fn create_entity(&mut self) -> Entity {
+        let entity = self.world.reserve_entity();
+        self.queue.push(move |world| world.components.spawn(entity));
+        entity
+    }
+
+    pub fn spawn<B>(&mut self, bundle: B) -> Entity
+    where
+        B: Bundle,
+    {
+        let entity = self.world.reserve_entity();
+        self.queue.push(move |world| world.insert_bundle(entity, bundle));
+        entity
+    }
+
+    pub fn destroy_entity(&mut self, entity: Entity) {
+        self.push_alive(entity, move |world| world.destroy_entity(entity));
+    }
+
+    pub fn insert_bundle<B>(&mut self, entity: Entity, bundle: B)
+    where
+        B: Bundle,
+    {
+        self.push_alive(entity, move |world| world.insert_bundle(entity, bundle));
+    }
+
+    pub fn remove_bundle<B>(&mut self, entity: Entity)
+    where
+        B: Bundle,
+    {
+        self.push_alive(entity, move |world| world.remove_bundle::<B>(entity));
+    }
+
+    pub fn add_component<C>(&mut self, entity: Entity, component: C)
+    where
+        C: Component,
+    {
+        self.push_alive(entity, move |world| world.add_component(entity, component));
+    }
+
+    pub fn remove_component<C>(&mut self, entity: Entity)
+    where
+        C: Component,
+    {
+        self.push_alive(entity, move |world| {
+            world.remove_component::<C>(entity);
+        });
+    }
+
+    pub fn insert_resource<R>(&mut self, resource: R)
+    where
+        R: Resource,
+    {
+        self.queue.push(move |world| {
+            world.insert_resource(resource);
+        });
+    }
+
+    pub fn remove_resource<R>(&mut self)
+    where
+        R: Resource,
+    {
+        self.queue.push(|world| {
+            world.remove_resource::<R>();
+        });
+    }
+
+    pub fn add<C>(&mut self, command: C)
+    where
+        C: FnOnce(&mut World) + Send + Sync + 'static,
+    {
+        self.queue.push(command);
+    }
+
+    fn push_alive<C>(&mut self, entity: Entity, command: C)
+    where
+        C: FnOnce(&mut World) + Send + Sync + 'static,
+    {
+        self.queue.push(move |world| {
+            if world.is_alive(entity) {
+                command(world);
+            }
+        });
+    }
+}
+
+impl SystemArg for Commands<'_> {
+    type Item<'o> = Commands<'o>;
+
+    type State = CommandQueue;
+
+    fn fetch<'i>(world: &'i World, state: &'i mut Self::State, _: Ticks) -> Self::Item<'i> {
+        Commands { world, queue: state }
+    }
 
-    fn fetch<'i>(components: &'i ComponentMap) -> Self::Item<'i> {
-        components.query_components_mut_filtered()
+    fn apply(state: &mut Self::State, world: &mut World) {
+        state.apply(world);
     }
 }
@@ -0,0 +1,259 @@
+use std::any::{TypeId, type_name};
+
+use crate::{
+    Component, ComponentHook, Entity, StorageType,
+    storage::{Column, ComponentMap, TypedColumn},
+};
+
+#[derive(Clone, Copy)]
+pub struct ComponentInfo {
+    pub(crate) id: TypeId,
+    pub(crate) name: &'static str,
+    pub(crate) storage: StorageType,
+    pub(crate) column: fn() -> Box<dyn Column>,
+    pub(crate) contains: fn(&ComponentMap, Entity) -> bool,
+    pub(crate) on_add: ComponentHook,
+    pub(crate) on_insert: ComponentHook,
+    pub(crate) on_replace: ComponentHook,
+    pub(crate) on_remove: ComponentHook,
+}
+
+impl ComponentInfo {
+    pub fn of<C>() -> Self
+    where
+        C: Component,
+    {
+        Self {
+            id: TypeId::of::<C>(),
+            name: type_name::<C>(),
+            storage: C::STORAGE,
+            column: || Box::new(TypedColumn::<C>::default()),
+            contains: ComponentMap::contains::<C>,
+            on_add: C::on_add,
+            on_insert: C::on_insert,
+            on_replace: C::on_replace,
+            on_remove: C::on_remove,
+        }
+    }
+}
+
+pub trait Bundle: Send + Sync + 'static {
+    fn components(infos: &mut Vec<ComponentInfo>);
+
+    fn write(self, components: &mut ComponentMap, entity: Entity);
+}
+
+pub(crate) fn bundle_components<B>() -> Vec<ComponentInfo>
+where
+    B: Bundle,
+{
+    let mut infos = Vec::new();
+    B::components(&mut infos);
+    for (idx, info) in infos.iter().enumerate() {
+        if infos[..idx].iter().any(|other| other.id == info.id) {
+            panic!("bundle {} contains {} more than once", type_name::<B>(), info.name);
+        }
+    }
+    infos
+}
+
+impl<C> Bundle for C
+where
+    C: Component,
+{
+    fn components(infos: &mut Vec<ComponentInfo>) {
+        infos.push(ComponentInfo::of::<C>());
+    }
+
+    fn write(self, components: &mut ComponentMap, entity: Entity) {
+        components.write(entity, self);
+    }
+}
+
+impl<A> Bundle for (A,)
+where
+    A: Bundle,
+{
+    fn components(infos: &mut Vec<ComponentInfo>) {
+        A::components(infos);
+    }
+
+    fn write(self, components: &mut ComponentMap, entity: Entity) {
+        self.0.write(components, entity);
+    }
+}
+
+impl<A, B> Bundle for (A, B)
+where
+    A: Bundle,
+    B: Bundle,
+{
+    fn components(infos: &mut Vec<ComponentInfo>) {
+        A::components(infos);
+        B::components(infos);
+    }
+
+    fn write(self, components: &mut ComponentMap, entity: Entity) {
+        self.0.write(components, entity);
+        self.1.write(components, entity);
+    }
+}
+
+impl<A, B, C> Bundle for (A, B, C)
+where
+    A: Bundle,
+    B: Bundle,
+    C: Bundle,
+{
+    fn components(infos: &mut Vec<ComponentInfo>) {
+        A::components(infos);
+        B::components(infos);
+        C::components(infos);
+    }
+
+    fn write(self, components: &mut ComponentMap, entity: Entity) {
+        self.0.write(components, entity);
+        self.1.write(components, entity);
+        self.2.write(components, entity);
+    }
+}
+
+impl<A, B, C, D> Bundle for (A, B, C, D)
+where
+    A: Bundle,
+    B: Bundle,
+    C: Bundle,
+    D: Bundle,
+{
+    fn components(infos: &mut Vec<ComponentInfo>) {
+        A::components(infos);
+        B::components(infos);
+        C::components(infos);
+        D::components(infos);
+    }
+
+    fn write(self, components: &mut ComponentMap, entity: Entity) {
+        self.0.write(components, entity);
+        self.1.write(components, entity);
+        self.2.write(components, entity);
+        self.3.write(components, entity);
+    }
+}
+
+impl<A, B, C, D, E> Bundle for (A, B, C, D, E)
+where
+    A: Bundle,
+    B: Bundle,
+    C: Bundle,
+    D: Bundle,
+    E: Bundle,
+{
+    fn components(infos: &mut Vec<ComponentInfo>) {
+        A::components(infos);
+        B::components(infos);
+        C::components(infos);
+        D::components(infos);
+        E::components(infos);
+    }
+
+    fn write(self, components: &mut ComponentMap, entity: Entity) {
+        self.0.write(components, entity);
+        self.1.write(components, entity);
+        self.2.write(components, entity);
+        self.3.write(components, entity);
+        self.4.write(components, entity);
+    }
+}
+
+impl<A, B, C, D, E, G> Bundle for (A, B, C, D, E, G)
+where
+    A: Bundle,
+    B: Bundle,
+    C: Bundle,
+    D: Bundle,
+    E: Bundle,
+    G: Bundle,
+{
+    fn components(infos: &mut Vec<ComponentInfo>) {
+        A::components(infos);
+        B::components(infos);
+        C::components(infos);
+        D::components(infos);
+        E::components(infos);
+        G::components(infos);
+    }
+
+    fn write(self, components: &mut ComponentMap, entity: Entity) {
+        self.0.write(components, entity);
+        self.1.write(components, entity);
+        self.2.write(components, entity);
+        self.3.write(components, entity);
+        self.4.write(components, entity);
+        self.5.write(components, entity);
+    }
+}
+
+impl<A, B, C, D, E, G, H> Bundle for (A, B, C, D, E, G, H)
+where
+    A: Bundle,
+    B: Bundle,
+    C: Bundle,
+    D: Bundle,
+    E: Bundle,
+    G: Bundle,
+    H: Bundle,
+{
+    fn components(infos: &mut Vec<ComponentInfo>) {
+        A::components(infos);
+        B::components(infos);
+        C::components(infos);
+        D::components(infos);
+        E::components(infos);
+        G::components(infos);
+        H::components(infos);
+    }
+
+    fn write(self, components: &mut ComponentMap, entity: Entity) {
+        self.0.write(components, entity);
+        self.1.write(components, entity);
+        self.2.write(components, entity);
+        self.3.write(components, entity);
+        self.4.write(components, entity);
+        self.5.write(components, entity);
+        self.6.write(components, entity);
+    }
+}
+
+impl<A, B, C, D, E, G, H, I> Bundle for (A, B, C, D, E, G, H, I)
+where
+    A: Bundle,
+    B: Bundle,
+    C: Bundle,
+    D: Bundle,
+    E: Bundle,
+    G: Bundle,
+    H: Bundle,
+    I: Bundle,
+{
+    fn components(infos: &mut Vec<ComponentInfo>) {
+        A::components(infos);
+        B::components(infos);
+        C::components(infos);
+        D::components(infos);
+        E::components(infos);
+        G::components(infos);
+        H::components(infos);
+        I::components(infos);
+    }
+
+    fn write(self, components: &mut ComponentMap, entity: Entity) {
+        self.0.write(components, entity);
+        self.1.write(components, entity);
+        self.2.write(components, entity);
+        self.3.write(components, entity);
+        self.4.write(components, entity);
+        self.5.write(components, entity);
+        self.6.write(components, entity);
+        self.7.write(components, entity);
+    }
+}
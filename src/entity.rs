@@ -0,0 +1,112 @@
+use std::{any::TypeId, sync::RwLockReadGuard};
+
+use crate::{Component, Entity, World, arguments::Mut, bundle::Bundle};
+
+pub struct EntityRef<'w> {
+    world: &'w World,
+    entity: Entity,
+}
+
+impl<'w> EntityRef<'w> {
+    pub(crate) fn new(world: &'w World, entity: Entity) -> Self {
+        Self { world, entity }
+    }
+
+    pub fn id(&self) -> Entity {
+        self.entity
+    }
+
+    pub fn get<C>(&self) -> Option<RwLockReadGuard<'w, C>>
+    where
+        C: Component,
+    {
+        Some(self.world.components.slot::<C>(self.entity)?.borrow())
+    }
+
+    pub fn contains<C>(&self) -> bool
+    where
+        C: Component,
+    {
+        self.world.components.contains::<C>(self.entity)
+    }
+
+    pub fn component_ids(&self) -> Vec<TypeId> {
+        self.world.components.component_types(self.entity).into_iter().map(|(id, _)| id).collect()
+    }
+
+    pub fn component_names(&self) -> Vec<&'static str> {
+        self.world.components.component_types(self.entity).into_iter().map(|(_, name)| name).collect()
+    }
+}
+
+pub struct EntityMut<'w> {
+    world: &'w mut World,
+    entity: Entity,
+}
+
+impl<'w> EntityMut<'w> {
+    pub(crate) fn new(world: &'w mut World, entity: Entity) -> Self {
+        Self { world, entity }
+    }
+
+    pub fn id(&self) -> Entity {
+        self.entity
+    }
+
+    pub fn get<C>(&self) -> Option<RwLockReadGuard<'_, C>>
+    where
+        C: Component,
+    {
+        EntityRef::new(self.world, self.entity).get()
+    }
+
+    pub fn get_mut<C>(&mut self) -> Option<Mut<'_, C>>
+    where
+        C: Component,
+    {
+        let tick = self.world.components.change_tick();
+        Some(self.world.components.slot::<C>(self.entity)?.borrow_tracked(tick))
+    }
+
+    pub fn contains<C>(&self) -> bool
+    where
+        C: Component,
+    {
+        self.world.components.contains::<C>(self.entity)
+    }
+
+    pub fn insert<C>(&mut self, component: C) -> &mut Self
+    where
+        C: Component,
+    {
+        self.world.add_component(self.entity, component);
+        self
+    }
+
+    pub fn insert_bundle<B>(&mut self, bundle: B) -> &mut Self
+    where
+        B: Bundle,
+    {
+        self.world.insert_bundle(self.entity, bundle);
+        self
+    }
+
+    pub fn remove<C>(&mut self) -> Option<C>
+    where
+        C: Component,
+    {
+        self.world.remove_component(self.entity)
+    }
+
+    pub fn despawn(self) {
+        self.world.destroy_entity(self.entity);
+    }
+
+    pub fn component_ids(&self) -> Vec<TypeId> {
+        EntityRef::new(self.world, self.entity).component_ids()
+    }
+
+    pub fn component_names(&self) -> Vec<&'static str> {
+        EntityRef::new(self.world, self.entity).component_names()
+    }
+}
@@ -0,0 +1,169 @@
+use std::{any::type_name, marker::PhantomData, mem};
+
+use crate::{
+    Event, Resource, World,
+    arguments::{Res, ResMut},
+    storage::Ticks,
+    systems::{Access, ReadOnlySystemArg, SystemArg},
+};
+
+pub struct Events<E> {
+    previous: Vec<E>,
+    current: Vec<E>,
+    previous_start: usize,
+    current_start: usize,
+}
+
+impl<E> Resource for Events<E> where E: Event {}
+
+impl<E> Default for Events<E> {
+    fn default() -> Self {
+        Self {
+            previous: Vec::new(),
+            current: Vec::new(),
+            previous_start: 0,
+            current_start: 0,
+        }
+    }
+}
+
+impl<E> Events<E> {
+    pub fn send(&mut self, event: E) {
+        self.current.push(event);
+    }
+
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn update(&mut self) {
+        mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+        self.previous_start = self.current_start;
+        self.current_start = self.previous_start + self.previous.len();
+    }
+
+    fn count(&self) -> usize {
+        self.current_start + self.current.len()
+    }
+
+    fn since(&self, cursor: usize) -> impl Iterator<Item = &E> {
+        let previous = cursor.saturating_sub(self.previous_start).min(self.previous.len());
+        let current = cursor.saturating_sub(self.current_start).min(self.current.len());
+        self.previous[previous..].iter().chain(&self.current[current..])
+    }
+}
+
+pub struct EventCursor<E> {
+    read: usize,
+    marker: PhantomData<fn() -> E>,
+}
+
+impl<E> Default for EventCursor<E> {
+    fn default() -> Self {
+        Self { read: 0, marker: PhantomData }
+    }
+}
+
+/// Systems taking an `EventWriter<E>` or `EventReader<E>` panic when they first run unless `E`
+/// was registered with `Master::add_event`.
+pub struct EventWriter<'d, E>
+where
+    E: Event,
+{
+    events: ResMut<'d, Events<E>>,
+}
+
+impl<E> EventWriter<'_, E>
+where
+    E: Event,
+{
+    pub fn send(&mut self, event: E) {
+        self.events.send(event);
+    }
+
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = E>) {
+        for event in events {
+            self.events.send(event);
+        }
+    }
+}
+
+impl<'d, E> SystemArg for EventWriter<'d, E>
+where
+    E: Event,
+{
+    type Item<'o> = EventWriter<'o, E>;
+
+    type State = ();
+
+    fn fetch<'i>(world: &'i World, _: &'i mut Self::State, _: Ticks) -> Self::Item<'i> {
+        EventWriter {
+            events: world.resource_mut().unwrap_or_else(|| unregistered::<E>()),
+        }
+    }
+
+    fn access(access: &mut Access) {
+        access.write_resource::<Events<E>>();
+    }
+}
+
+pub struct EventReader<'d, E>
+where
+    E: Event,
+{
+    events: Res<'d, Events<E>>,
+    cursor: &'d mut EventCursor<E>,
+}
+
+impl<E> EventReader<'_, E>
+where
+    E: Event,
+{
+    pub fn read(&mut self) -> impl Iterator<Item = &E> {
+        let cursor = mem::replace(&mut self.cursor.read, self.events.count());
+        self.events.since(cursor)
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.since(self.cursor.read).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.cursor.read = self.events.count();
+    }
+}
+
+impl<'d, E> SystemArg for EventReader<'d, E>
+where
+    E: Event,
+{
+    type Item<'o> = EventReader<'o, E>;
+
+    type State = EventCursor<E>;
+
+    fn fetch<'i>(world: &'i World, state: &'i mut Self::State, _: Ticks) -> Self::Item<'i> {
+        EventReader {
+            events: world.resource().unwrap_or_else(|| unregistered::<E>()),
+            cursor: state,
+        }
+    }
+
+    fn access(access: &mut Access) {
+        access.read_resource::<Events<E>>();
+    }
+}
+
+impl<'d, E> ReadOnlySystemArg for EventReader<'d, E> where E: Event {}
+
+pub(crate) fn unregistered<E>() -> ! {
+    panic!("event {} is not registered", type_name::<E>())
+}
@@ -1,31 +1,41 @@
 pub mod arguments;
+pub mod bundle;
+pub mod entity;
+pub mod events;
+pub mod schedule;
+pub mod storage;
 pub mod systems;
+pub mod time;
 
 use std::{
-    any::{Any, TypeId},
-    cell::RefCell,
-    collections::HashMap,
-    marker::PhantomData,
+    any::{Any, TypeId, type_name},
     ops::{Deref, DerefMut},
-    sync::{LazyLock, Mutex, MutexGuard},
+    sync::{LazyLock, Mutex, MutexGuard, RwLockReadGuard, RwLockWriteGuard},
+    time::{Duration, Instant},
 };
 
+pub use crate::storage::ComponentMap;
+
 use crate::{
-    arguments::{InnerCluster, InnerClusterMut, Query, QueryFilter, QueryMut},
-    systems::SystemBuilder,
+    arguments::{Query, QueryData, QueryMut, Res, ResMut},
+    bundle::{Bundle, bundle_components},
+    entity::{EntityMut, EntityRef},
+    events::{Events, unregistered},
+    schedule::{Constraints, ScheduleError, Stage, SystemGroup, SystemLabel, SystemStage, WorkerPool},
+    storage::{Slot, TypeIdMap},
+    systems::{Access, SystemBuilder, SystemError, SystemId},
+    time::{Clock, FixedTime, Time},
 };
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Entity {
     id: usize,
+    generation: u32,
 }
 
-impl<Id> From<Id> for Entity
-where
-    Id: Into<usize>,
-{
-    fn from(value: Id) -> Self {
-        Self { id: value.into() }
+impl Entity {
+    pub fn generation(&self) -> u32 {
+        self.generation
     }
 }
 
@@ -37,23 +47,81 @@ impl Deref for Entity {
     }
 }
 
-pub trait Component: Any + Send + Sync {}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageType {
+    Table,
+    SparseSet,
+}
+
+pub type ComponentHook = fn(&mut World, Entity);
 
-impl dyn Component {
-    fn as_any(&self) -> &dyn Any {
-        self as &dyn Any
-    }
+pub trait Component: Any + Send + Sync {
+    const STORAGE: StorageType = StorageType::Table;
 
-    fn as_any_mut(&mut self) -> &mut dyn Any {
-        self as &mut dyn Any
-    }
+    fn on_add(_: &mut World, _: Entity) {}
+
+    fn on_insert(_: &mut World, _: Entity) {}
+
+    fn on_replace(_: &mut World, _: Entity) {}
+
+    fn on_remove(_: &mut World, _: Entity) {}
 }
 
+pub trait Resource: Any + Send + Sync {}
+
+pub trait Event: Any + Send + Sync {}
+
 pub trait System
 where
     Self: Send + Sync,
 {
-    fn run(&mut self, map: &mut ComponentMap);
+    fn run(&mut self, world: &World);
+
+    fn apply(&mut self, _: &mut World) {}
+
+    fn name(&self) -> &'static str {
+        type_name::<Self>()
+    }
+
+    fn label(&self) -> SystemLabel;
+
+    fn access(&self) -> Access;
+
+    fn constraints(&self, _: &mut Constraints) {}
+
+    fn validate(&self) -> Result<(), SystemError> {
+        self.access().validate(self.name())
+    }
+}
+
+impl System for Box<dyn System> {
+    fn run(&mut self, world: &World) {
+        (**self).run(world);
+    }
+
+    fn apply(&mut self, world: &mut World) {
+        (**self).apply(world);
+    }
+
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn label(&self) -> SystemLabel {
+        (**self).label()
+    }
+
+    fn access(&self) -> Access {
+        (**self).access()
+    }
+
+    fn constraints(&self, constraints: &mut Constraints) {
+        (**self).constraints(constraints);
+    }
+
+    fn validate(&self) -> Result<(), SystemError> {
+        (**self).validate()
+    }
 }
 
 impl<S> From<Box<S>> for Box<dyn System>
@@ -81,173 +149,1789 @@ pub fn master_check() -> bool {
 }
 
 #[derive(Default)]
-pub struct ComponentMap {
-    inner: HashMap<TypeId, HashMap<Entity, RefCell<Box<dyn Component + 'static>>>>,
+pub struct ResourceMap {
+    inner: TypeIdMap<Box<dyn Any + Send + Sync>>,
 }
 
-impl ComponentMap {
-    fn has_component<C>(&self, entity: Entity) -> bool
-    where
-        C: 'static,
-    {
-        self.get(&TypeId::of::<C>()).map(|outer| outer.contains_key(&entity)).unwrap_or(false)
-    }
-
-    fn query_components<'d, C>(&'d self) -> Query<'d, C, ()>
+impl ResourceMap {
+    fn slot<R>(&self) -> Option<&Slot<R>>
     where
-        C: 'static,
+        R: Resource,
     {
-        Query {
-            inner: self
-                .get(&TypeId::of::<C>())
-                .into_iter()
-                .flat_map(|outer| outer.iter())
-                .map(|(entity, inner)| InnerCluster { entity: *entity, component: inner.borrow() })
-                .collect(),
-            marker: PhantomData,
-            fmarker: PhantomData,
-        }
+        self.get(&TypeId::of::<R>())?.downcast_ref()
     }
 
-    fn query_components_filtered<'d, C, F>(&'d self) -> Query<'d, C, F>
+    fn slot_mut<R>(&mut self) -> Option<&mut R>
     where
-        C: 'static,
-        F: QueryFilter,
+        R: Resource,
     {
-        Query {
-            inner: self
-                .query_components::<C>()
-                .inner
-                .into_iter()
-                .filter(|cluster| F::matches(self, cluster.entity))
-                .collect(),
-            marker: PhantomData,
-            fmarker: PhantomData,
-        }
+        Some(self.get_mut(&TypeId::of::<R>())?.downcast_mut::<Slot<R>>()?.get_mut())
     }
 
-    fn query_components_mut<'d, C>(&'d self) -> QueryMut<'d, C, ()>
+    fn get_resource<R>(&self) -> Option<RwLockReadGuard<'_, R>>
     where
-        C: 'static,
+        R: Resource,
     {
-        QueryMut {
-            inner: self
-                .get(&TypeId::of::<C>())
-                .into_iter()
-                .flat_map(|outer| outer.iter())
-                .map(|(entity, inner)| InnerClusterMut { entity: *entity, component: inner.borrow_mut() })
-                .collect(),
-            marker: PhantomData,
-            fmarker: PhantomData,
-        }
+        Some(self.slot()?.borrow())
     }
 
-    fn query_components_mut_filtered<'d, C, F>(&'d self) -> QueryMut<'d, C, F>
+    fn get_resource_mut<R>(&self) -> Option<RwLockWriteGuard<'_, R>>
     where
-        C: 'static,
-        F: QueryFilter,
+        R: Resource,
     {
-        QueryMut {
-            inner: self
-                .query_components_mut::<C>()
-                .inner
-                .into_iter()
-                .filter(|cluster| F::matches(self, cluster.entity))
-                .collect(),
-            marker: PhantomData,
-            fmarker: PhantomData,
-        }
+        Some(self.slot()?.borrow_mut())
     }
 }
 
-impl Deref for ComponentMap {
-    type Target = HashMap<TypeId, HashMap<Entity, RefCell<Box<dyn Component>>>>;
+impl Deref for ResourceMap {
+    type Target = TypeIdMap<Box<dyn Any + Send + Sync>>;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
     }
 }
 
-impl DerefMut for ComponentMap {
+impl DerefMut for ResourceMap {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.inner
     }
 }
 
 #[derive(Default)]
-pub struct Master {
-    curr_entity: Entity,
+struct EntitySlot {
+    generation: u32,
+    alive: bool,
+}
+
+#[derive(Default)]
+struct Entities {
+    slots: Vec<EntitySlot>,
+    free: Vec<usize>,
+}
+
+impl Entities {
+    fn allocate(&mut self) -> Entity {
+        let id = self.free.pop().unwrap_or_else(|| {
+            self.slots.push(EntitySlot::default());
+            self.slots.len() - 1
+        });
+        let slot = &mut self.slots[id];
+        slot.alive = true;
+        Entity { id, generation: slot.generation }
+    }
+
+    fn free(&mut self, entity: Entity) {
+        let slot = &mut self.slots[entity.id];
+        slot.alive = false;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(entity.id);
+    }
+
+    fn is_alive(&self, entity: Entity) -> bool {
+        self.slots.get(entity.id).is_some_and(|slot| slot.alive && slot.generation == entity.generation)
+    }
+}
+
+#[derive(Default)]
+pub struct World {
+    entities: Mutex<Entities>,
     components: ComponentMap,
-    systems: Vec<Box<dyn System>>,
+    resources: ResourceMap,
 }
 
-impl Master {
+impl World {
     pub fn create_entity(&mut self) -> Entity {
-        let out = self.curr_entity;
-        self.curr_entity.id += 1;
-        out
+        let entity = self.entities.get_mut().unwrap().allocate();
+        self.components.spawn(entity);
+        entity
+    }
+
+    fn reserve_entity(&self) -> Entity {
+        self.entities.lock().unwrap().allocate()
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entities.lock().unwrap().is_alive(entity)
+    }
+
+    fn assert_alive(&self, entity: Entity) {
+        if !self.is_alive(entity) {
+            panic!("entity {} (generation {}) is not alive", entity.id, entity.generation);
+        }
+    }
+
+    pub fn entity(&self, entity: Entity) -> EntityRef<'_> {
+        self.assert_alive(entity);
+        EntityRef::new(self, entity)
+    }
+
+    pub fn entity_mut(&mut self, entity: Entity) -> EntityMut<'_> {
+        self.assert_alive(entity);
+        EntityMut::new(self, entity)
     }
 
     pub fn destroy_entity(&mut self, entity: Entity) {
-        self.components.values_mut().for_each(|component| {
-            component.remove(&entity);
-        });
+        self.assert_alive(entity);
+        let hooks = self.components.removal_hooks(entity);
+        for hook in hooks.iter() {
+            (hook.on_replace)(self, entity);
+        }
+        for hook in hooks.iter() {
+            (hook.on_remove)(self, entity);
+        }
+        self.components.despawn(entity);
+        self.entities.get_mut().unwrap().free(entity);
     }
 
     pub fn add_component<C>(&mut self, entity: Entity, component: C)
     where
         C: Component + 'static,
     {
-        self.components
-            .inner
-            .entry(TypeId::of::<C>())
-            .or_default()
-            .insert(entity, RefCell::new(Box::new(component)));
+        self.replace_component(entity, component);
     }
 
-    pub fn remove_component<C>(&mut self, entity: Entity)
+    pub fn replace_component<C>(&mut self, entity: Entity, component: C) -> Option<C>
     where
         C: Component + 'static,
     {
-        self.components.inner.get_mut(&TypeId::of::<C>()).and_then(|outer| outer.remove(&entity));
+        self.assert_alive(entity);
+        let replacing = self.components.contains::<C>(entity);
+        if replacing {
+            C::on_replace(self, entity);
+        }
+        let previous = self.components.insert(entity, component);
+        if !replacing {
+            C::on_add(self, entity);
+        }
+        C::on_insert(self, entity);
+        previous
+    }
+
+    pub fn remove_component<C>(&mut self, entity: Entity) -> Option<C>
+    where
+        C: Component + 'static,
+    {
+        self.assert_alive(entity);
+        if self.components.contains::<C>(entity) {
+            C::on_replace(self, entity);
+            C::on_remove(self, entity);
+        }
+        self.components.remove::<C>(entity)
+    }
+
+    pub fn take_component<C>(&mut self, entity: Entity) -> C
+    where
+        C: Component + 'static,
+    {
+        self.remove_component(entity).unwrap_or_else(|| {
+            panic!(
+                "entity {} (generation {}) has no component {}",
+                entity.id,
+                entity.generation,
+                type_name::<C>()
+            )
+        })
+    }
+
+    pub fn spawn<B>(&mut self, bundle: B) -> Entity
+    where
+        B: Bundle,
+    {
+        let entity = self.create_entity();
+        self.insert_bundle(entity, bundle);
+        entity
+    }
+
+    pub fn insert_bundle<B>(&mut self, entity: Entity, bundle: B)
+    where
+        B: Bundle,
+    {
+        self.assert_alive(entity);
+        let infos = bundle_components::<B>();
+        let present: Vec<bool> = infos.iter().map(|info| (info.contains)(&self.components, entity)).collect();
+        for (info, _) in infos.iter().zip(&present).filter(|(_, present)| **present) {
+            (info.on_replace)(self, entity);
+        }
+        self.components.insert_bundle(entity, &infos);
+        bundle.write(&mut self.components, entity);
+        for (info, _) in infos.iter().zip(&present).filter(|(_, present)| !**present) {
+            (info.on_add)(self, entity);
+        }
+        for info in infos.iter() {
+            (info.on_insert)(self, entity);
+        }
+    }
+
+    pub fn remove_bundle<B>(&mut self, entity: Entity)
+    where
+        B: Bundle,
+    {
+        self.assert_alive(entity);
+        let infos = bundle_components::<B>();
+        let present: Vec<_> = infos.iter().filter(|info| (info.contains)(&self.components, entity)).collect();
+        for info in present.iter() {
+            (info.on_replace)(self, entity);
+        }
+        for info in present.iter() {
+            (info.on_remove)(self, entity);
+        }
+        self.components.remove_bundle(entity, &infos);
+    }
+
+    pub fn insert_resource<R>(&mut self, resource: R) -> Option<R>
+    where
+        R: Resource,
+    {
+        let previous = self.remove_resource::<R>();
+        self.resources.inner.insert(TypeId::of::<R>(), Box::new(Slot::new(resource)));
+        previous
+    }
+
+    pub fn remove_resource<R>(&mut self) -> Option<R>
+    where
+        R: Resource,
+    {
+        let slot = self.resources.inner.remove(&TypeId::of::<R>())?;
+        Some(slot.downcast::<Slot<R>>().unwrap().into_inner())
+    }
+
+    pub fn contains_resource<R>(&self) -> bool
+    where
+        R: Resource,
+    {
+        self.resources.contains_key(&TypeId::of::<R>())
+    }
+
+    pub fn resource<R>(&self) -> Option<Res<'_, R>>
+    where
+        R: Resource,
+    {
+        Some(Res { inner: self.resources.get_resource()? })
+    }
+
+    pub fn resource_mut<R>(&self) -> Option<ResMut<'_, R>>
+    where
+        R: Resource,
+    {
+        Some(ResMut { inner: self.resources.get_resource_mut()? })
     }
 
+    /// Panics if `E` was never registered with `Master::add_event`.
+    pub fn send_event<E>(&self, event: E)
+    where
+        E: Event,
+    {
+        self.resource_mut::<Events<E>>().unwrap_or_else(|| unregistered::<E>()).send(event);
+    }
+
+    pub fn query<'d, C>(&'d self) -> Query<'d, C>
+    where
+        C: QueryData,
+    {
+        Query::new(&self.components, self.components.current_ticks())
+    }
+
+    pub fn query_mut<'d, C>(&'d self) -> QueryMut<'d, C>
+    where
+        C: QueryData,
+    {
+        QueryMut::new(&self.components, self.components.current_ticks())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Executor {
+    /// Runs a stage's systems one at a time, applying each system's commands right after it runs.
+    #[default]
+    SingleThreaded,
+    /// Runs non-conflicting systems in parallel waves on a persistent worker pool. Commands are
+    /// applied in schedule order once the whole wave has finished, so systems in the same wave
+    /// never see each other's commands.
+    MultiThreaded,
+}
+
+pub struct Master {
+    world: World,
+    startup: SystemStage,
+    stages: Vec<SystemStage>,
+    started: bool,
+    next_system: usize,
+    executor: Executor,
+    workers: WorkerPool,
+    clock: Clock,
+    last_frame: Option<Instant>,
+    events: Vec<fn(&mut World)>,
+    removal_tick: u64,
+}
+
+impl Default for Master {
+    fn default() -> Self {
+        let mut world = World::default();
+        world.insert_resource(Time::default());
+        world.insert_resource(FixedTime::default());
+        Self {
+            world,
+            startup: SystemStage::new(Stage::Startup),
+            stages: [Stage::PreUpdate, Stage::FixedUpdate, Stage::Update, Stage::PostUpdate]
+                .map(SystemStage::new)
+                .into(),
+            started: false,
+            next_system: 0,
+            executor: Executor::default(),
+            workers: WorkerPool::default(),
+            clock: Clock::default(),
+            last_frame: None,
+            events: Vec::new(),
+            removal_tick: 0,
+        }
+    }
+}
+
+impl Master {
     pub fn run(&mut self) {
-        for system in self.systems.iter_mut() {
-            system.run(&mut self.components);
+        self.build_schedule().unwrap_or_else(|error| panic!("{}", error));
+        let tick = self.world.components.change_tick();
+        self.world.components.trim_removed(std::mem::replace(&mut self.removal_tick, tick));
+        if !self.started {
+            self.started = true;
+            self.startup.run(&mut self.world, self.executor, &self.workers);
+        }
+        let delta = self.advance_time();
+        for update in self.events.iter() {
+            update(&mut self.world);
+        }
+        for stage in self.stages.iter_mut() {
+            match stage.stage {
+                | Stage::FixedUpdate => {
+                    Self::run_fixed(&mut self.world, stage, self.executor, &self.workers, delta)
+                }
+                | _ => stage.run(&mut self.world, self.executor, &self.workers),
+            }
+        }
+    }
+
+    fn run_fixed(
+        world: &mut World,
+        stage: &mut SystemStage,
+        executor: Executor,
+        workers: &WorkerPool,
+        delta: Duration,
+    ) {
+        if stage.systems.is_empty() {
+            return;
+        }
+        let Some(step) = world.resources.slot_mut::<FixedTime>().map(|fixed| {
+            fixed.accumulate(delta);
+            fixed.step()
+        }) else {
+            return;
+        };
+        let frame_delta = world.resources.slot_mut::<Time>().map(|time| time.set_delta(step));
+        while world.resources.slot_mut::<FixedTime>().is_some_and(FixedTime::expend) {
+            stage.run(world, executor, workers);
+        }
+        if let (Some(frame_delta), Some(time)) = (frame_delta, world.resources.slot_mut::<Time>()) {
+            time.set_delta(frame_delta);
+        }
+    }
+
+    fn advance_time(&mut self) -> Duration {
+        let raw = match self.clock {
+            | Clock::Real => {
+                let now = Instant::now();
+                let raw = self.last_frame.map_or(Duration::ZERO, |last| now - last);
+                self.last_frame = Some(now);
+                raw
+            }
+            | Clock::Manual(delta) => delta,
+        };
+        self.world.resources.slot_mut::<Time>().map_or(Duration::ZERO, |time| {
+            time.update(raw);
+            time.delta()
+        })
+    }
+
+    pub fn add_event<E>(&mut self)
+    where
+        E: Event,
+    {
+        if self.world.contains_resource::<Events<E>>() {
+            return;
+        }
+        self.world.insert_resource(Events::<E>::default());
+        self.events.push(|world| {
+            if let Some(mut events) = world.resource_mut::<Events<E>>() {
+                events.update();
+            }
+        });
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+        self.last_frame = None;
+    }
+
+    pub fn build_schedule(&mut self) -> Result<(), ScheduleError> {
+        self.all_stages_mut().try_for_each(SystemStage::build)
+    }
+
+    pub fn set_executor(&mut self, executor: Executor) {
+        self.executor = executor;
+    }
+
+    fn stage_mut(&mut self, stage: Stage) -> Option<&mut SystemStage> {
+        match stage {
+            | Stage::Startup => Some(&mut self.startup),
+            | _ => self.stages.iter_mut().find(|other| other.stage == stage),
+        }
+    }
+
+    fn all_stages(&self) -> impl Iterator<Item = &SystemStage> {
+        std::iter::once(&self.startup).chain(&self.stages)
+    }
+
+    fn all_stages_mut(&mut self) -> impl Iterator<Item = &mut SystemStage> {
+        std::iter::once(&mut self.startup).chain(&mut self.stages)
+    }
+
+    pub fn add_stage_before(&mut self, anchor: Stage, stage: Stage) {
+        let idx = self.stage_index(anchor, stage);
+        self.stages.insert(idx, SystemStage::new(stage));
+    }
+
+    pub fn add_stage_after(&mut self, anchor: Stage, stage: Stage) {
+        let idx = self.stage_index(anchor, stage);
+        self.stages.insert(idx + 1, SystemStage::new(stage));
+    }
+
+    fn stage_index(&self, anchor: Stage, stage: Stage) -> usize {
+        if self.all_stages().any(|other| other.stage == stage) {
+            panic!("stage {:?} already exists", stage);
+        }
+        match self.stages.iter().position(|other| other.stage == anchor) {
+            | Some(idx) => idx,
+            | None => panic!("stage {:?} can't be used as an anchor", anchor),
         }
     }
 
-    pub fn add_system<A, I, S>(&mut self, system: A)
+    pub fn stages(&self) -> impl Iterator<Item = Stage> {
+        self.all_stages().map(|stage| stage.stage)
+    }
+
+    pub fn add_system<A, I, S>(&mut self, system: A) -> SystemId
     where
-        A: SystemBuilder<I, System = S> + Copy,
+        A: SystemBuilder<I, System = S>,
         S: System + 'static,
     {
-        self.systems.push(Box::new(system.build_system()));
+        self.add_system_to_stage(Stage::Update, system)
     }
 
-    pub fn remove_system<A, I, S>(&mut self, system: A)
+    pub fn add_startup_system<A, I, S>(&mut self, system: A) -> SystemId
     where
-        A: SystemBuilder<I, System = S> + Copy,
+        A: SystemBuilder<I, System = S>,
         S: System + 'static,
     {
-        self.systems.retain(|sys| (**sys).type_id() == system.build_system().type_id());
+        self.add_system_to_stage(Stage::Startup, system)
     }
 
-    pub fn query<'d, C>(&'d self) -> Query<'d, C>
+    pub fn add_system_to_stage<A, I, S>(&mut self, stage: Stage, system: A) -> SystemId
     where
-        C: 'static,
+        A: SystemBuilder<I, System = S>,
+        S: System + 'static,
     {
-        self.components.query_components::<C>()
+        self.try_add_system_to_stage(stage, system).unwrap_or_else(|error| panic!("{}", error))
     }
 
-    pub fn query_mut<'d, C>(&'d self) -> QueryMut<'d, C>
+    pub fn try_add_system<A, I, S>(&mut self, system: A) -> Result<SystemId, SystemError>
+    where
+        A: SystemBuilder<I, System = S>,
+        S: System + 'static,
+    {
+        self.try_add_system_to_stage(Stage::Update, system)
+    }
+
+    pub fn try_add_system_to_stage<A, I, S>(
+        &mut self,
+        stage: Stage,
+        system: A,
+    ) -> Result<SystemId, SystemError>
+    where
+        A: SystemBuilder<I, System = S>,
+        S: System + 'static,
+    {
+        let system = system.build_system();
+        system.validate()?;
+        let id = SystemId(self.next_system);
+        let target = self.stage_mut(stage).ok_or(SystemError::UnknownStage(stage))?;
+        target.systems.push((id, Box::new(system)));
+        target.dirty = true;
+        self.next_system += 1;
+        Ok(id)
+    }
+
+    pub fn add_systems<G, M>(&mut self, group: G) -> Vec<SystemId>
     where
-        C: 'static,
+        G: SystemGroup<M>,
     {
-        self.components.query_components_mut::<C>()
+        self.add_systems_to_stage(Stage::Update, group)
+    }
+
+    pub fn add_systems_to_stage<G, M>(&mut self, stage: Stage, group: G) -> Vec<SystemId>
+    where
+        G: SystemGroup<M>,
+    {
+        self.try_add_systems_to_stage(stage, group).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_add_systems_to_stage<G, M>(
+        &mut self,
+        stage: Stage,
+        group: G,
+    ) -> Result<Vec<SystemId>, SystemError>
+    where
+        G: SystemGroup<M>,
+    {
+        let chained = group.chained();
+        let mut systems = Vec::new();
+        group.build_systems(&mut systems);
+        for system in systems.iter() {
+            system.validate()?;
+        }
+        let first = self.next_system;
+        let target = self.stage_mut(stage).ok_or(SystemError::UnknownStage(stage))?;
+        let ids: Vec<SystemId> = (first..first + systems.len()).map(SystemId).collect();
+        target.systems.extend(ids.iter().copied().zip(systems));
+        if chained {
+            target.chained.extend(ids.windows(2).map(|pair| (pair[0], pair[1])));
+        }
+        target.dirty = true;
+        self.next_system += ids.len();
+        Ok(ids)
+    }
+
+    pub fn remove_system(&mut self, id: SystemId) -> bool {
+        self.all_stages_mut().any(|stage| {
+            let count = stage.systems.len();
+            stage.systems.retain(|(other, _)| *other != id);
+            let before: Vec<SystemId> =
+                stage.chained.iter().filter(|(_, second)| *second == id).map(|(first, _)| *first).collect();
+            let after: Vec<SystemId> =
+                stage.chained.iter().filter(|(first, _)| *first == id).map(|(_, second)| *second).collect();
+            stage.chained.retain(|(first, second)| *first != id && *second != id);
+            stage
+                .chained
+                .extend(before.iter().flat_map(|first| after.iter().map(|second| (*first, *second))));
+            stage.dirty |= stage.systems.len() != count;
+            stage.systems.len() != count
+        })
+    }
+
+    pub fn replace_system<A, I, S>(&mut self, id: SystemId, system: A) -> Result<(), SystemError>
+    where
+        A: SystemBuilder<I, System = S>,
+        S: System + 'static,
+    {
+        let system = system.build_system();
+        system.validate()?;
+        let stage = self
+            .all_stages_mut()
+            .find(|stage| stage.systems.iter().any(|(other, _)| *other == id))
+            .ok_or(SystemError::UnknownSystem(id))?;
+        let (_, slot) = stage.systems.iter_mut().find(|(other, _)| *other == id).unwrap();
+        *slot = Box::new(system);
+        stage.dirty = true;
+        Ok(())
+    }
+
+    pub fn systems(&self) -> impl Iterator<Item = (SystemId, &'static str)> {
+        self.all_stages().flat_map(|stage| stage.systems.iter()).map(|(id, system)| (*id, system.name()))
+    }
+}
+
+impl Deref for Master {
+    type Target = World;
+
+    fn deref(&self) -> &Self::Target {
+        &self.world
+    }
+}
+
+impl DerefMut for Master {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.world
     }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System as SystemAlloc},
+        cell::Cell,
+        thread,
+    };
+
+    use super::*;
+    use crate::arguments::QueryError;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    struct CountingAlloc;
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            unsafe { SystemAlloc.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { SystemAlloc.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+    impl Component for Position {}
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+
+    impl Component for Velocity {}
+    #[derive(Debug, PartialEq)]
+    struct Velocity(i32);
+
+    impl Component for Mass {}
+    #[derive(Debug, PartialEq)]
+    struct Mass(i32);
+
+    #[test]
+    fn tuple_query_joins_on_entity() {
+        let mut master = Master::default();
+        for idx in 0..4 {
+            let entity = master.create_entity();
+            master.add_component(entity, Position(idx));
+            if idx != 2 {
+                master.add_component(entity, Velocity(idx * 10));
+            }
+            if idx % 2 == 1 {
+                master.add_component(entity, Mass(idx * 100));
+            }
+        }
+
+        for (mut pos, vel, mass) in master.query_mut::<(&mut Position, &Velocity, Option<&Mass>)>() {
+            assert_eq!(pos.0 * 10, vel.0);
+            if let Some(mass) = mass {
+                assert_eq!(pos.0 * 100, mass.0);
+            }
+            pos.0 += vel.0;
+        }
+
+        let mut positions: Vec<i32> = master.query::<Position>().iter().map(|pos| pos.0).collect();
+        positions.sort();
+        assert_eq!(positions, vec![0, 2, 11, 33]);
+    }
+
+    #[test]
+    fn entity_fetch_matches_owner() {
+        let mut master = Master::default();
+        let first = master.create_entity();
+        let second = master.create_entity();
+        master.add_component(first, Position(1));
+        master.add_component(second, Position(2));
+        master.add_component(second, Velocity(0));
+
+        let owners: Vec<(Entity, i32)> =
+            master.query::<(Entity, &Position)>().iter().map(|(entity, pos)| (entity, pos.0)).collect();
+        assert_eq!(owners, vec![(first, 1), (second, 2)]);
+
+        let moving: Vec<Entity> =
+            master.query::<(Entity, &Velocity)>().iter().map(|(entity, _)| entity).collect();
+        assert_eq!(moving, vec![second]);
+    }
+
+    #[test]
+    fn shared_access_to_mutable_tuple_queries_reads() {
+        let mut master = Master::default();
+        let entity = master.spawn(Position(1));
+
+        let query = master.query_mut::<(&mut Position, Entity)>();
+        let (first, _) = query.get(entity).unwrap();
+        let (second, owner) = query.get(entity).unwrap();
+        assert_eq!((first.0, second.0, owner), (1, 1, entity));
+        assert_eq!(query.iter().map(|(pos, _)| pos.0).sum::<i32>(), 1);
+    }
+
+    #[test]
+    fn query_lookup_by_entity() {
+        use crate::arguments::Without;
+
+        let mut master = Master::default();
+        let still = master.create_entity();
+        let moving = master.create_entity();
+        let empty = master.create_entity();
+        master.add_component(still, Position(1));
+        master.add_component(moving, Position(2));
+        master.add_component(moving, Velocity(3));
+        master.add_component(empty, Mass(0));
+
+        let query =
+            Query::<Position, Without<Velocity>>::new(&master.components, master.components.current_ticks());
+        assert_eq!(query.get(still).map(|pos| pos.0), Ok(1));
+        assert!(query.contains(still));
+        assert_eq!(query.get(moving).err(), Some(QueryError::Filtered(moving)));
+        assert_eq!(
+            query.get(empty).err(),
+            Some(QueryError::MissingComponent {
+                entity: empty,
+                component: std::any::type_name::<Position>()
+            })
+        );
+
+        let mut query = master.query_mut::<Position>();
+        let [mut first, mut second] = query.get_many_mut([still, moving]).unwrap();
+        std::mem::swap(&mut first.0, &mut second.0);
+        drop((first, second));
+        assert_eq!(query.get_many_mut([moving, moving]).err(), Some(QueryError::AliasedMutability(moving)));
+        assert_eq!(query.get(still).map(|pos| pos.0), Ok(2));
+    }
+
+    #[test]
+    fn archetype_moves_keep_rows_consistent() {
+        let mut master = Master::default();
+        let entities: Vec<Entity> = (0..4)
+            .map(|idx| {
+                let entity = master.create_entity();
+                master.add_component(entity, Position(idx));
+                master.add_component(entity, Velocity(idx * 10));
+                entity
+            })
+            .collect();
+        master.remove_component::<Velocity>(entities[0]);
+        master.add_component(entities[1], Mass(100));
+        master.add_component(entities[3], Velocity(30));
+        master.destroy_entity(entities[2]);
+
+        let query = master.query::<(&Position, Option<&Velocity>, Option<&Mass>)>();
+        let fetch = |idx: usize| {
+            query
+                .get(entities[idx])
+                .map(|(pos, vel, mass)| (pos.0, vel.map(|vel| vel.0), mass.map(|mass| mass.0)))
+        };
+        assert_eq!(fetch(0), Ok((0, None, None)));
+        assert_eq!(fetch(1), Ok((1, Some(10), Some(100))));
+        assert_eq!(fetch(2).err(), Some(QueryError::NoSuchEntity(entities[2])));
+        assert_eq!(fetch(3), Ok((3, Some(30), None)));
+        assert_eq!(master.query::<Entity>().iter().count(), 3);
+    }
+
+    #[test]
+    fn sparse_components_mix_with_tables() {
+        use crate::arguments::{With, Without};
+
+        impl Component for Stunned {
+            const STORAGE: StorageType = StorageType::SparseSet;
+        }
+        #[derive(Debug, PartialEq)]
+        struct Stunned(i32);
+
+        let mut master = Master::default();
+        let entities: Vec<Entity> = (0..3)
+            .map(|idx| {
+                let entity = master.create_entity();
+                master.add_component(entity, Position(idx));
+                entity
+            })
+            .collect();
+        let archetypes = master.components.archetypes().len();
+        master.add_component(entities[0], Stunned(5));
+        master.add_component(entities[2], Stunned(7));
+        master.remove_component::<Stunned>(entities[2]);
+        master.add_component(entities[1], Stunned(6));
+        assert_eq!(master.components.archetypes().len(), archetypes);
+
+        for (pos, mut stun) in master.query_mut::<(&Position, &mut Stunned)>() {
+            stun.0 += pos.0;
+        }
+        let stuns: Vec<(Entity, i32)> =
+            master.query::<(Entity, &Stunned)>().iter().map(|(entity, stun)| (entity, stun.0)).collect();
+        assert_eq!(stuns, vec![(entities[0], 5), (entities[1], 7)]);
+
+        let free: Vec<Entity> =
+            Query::<Entity, Without<Stunned>>::new(&master.components, master.components.current_ticks())
+                .iter()
+                .collect();
+        assert_eq!(free, vec![entities[2]]);
+        let stunned =
+            Query::<Position, With<Stunned>>::new(&master.components, master.components.current_ticks());
+        assert_eq!(stunned.iter().count(), 2);
+        assert_eq!(
+            master.query::<(&Position, &Stunned)>().get(entities[2]).err(),
+            Some(QueryError::MissingComponent {
+                entity: entities[2],
+                component: std::any::type_name::<Stunned>()
+            })
+        );
+
+        master.destroy_entity(entities[0]);
+        assert_eq!(master.query::<Stunned>().iter().count(), 1);
+    }
+
+    #[test]
+    fn steady_state_run_does_not_allocate() {
+        use crate::arguments::{Commands, With};
+
+        fn integrate(query: QueryMut<(&mut Position, &Velocity)>, _: Commands) {
+            for (mut pos, vel) in query {
+                pos.0 += vel.0;
+            }
+        }
+
+        fn weigh(query: Query<(Entity, &Position, Option<&Mass>), With<Velocity>>, mut total: ResMut<Total>) {
+            let (first, ..) = query.iter().next().unwrap();
+            total.0 = query.get(first).map(|(_, pos, _)| pos.0).unwrap();
+        }
+
+        impl Resource for Total {}
+        struct Total(i32);
+
+        let mut master = Master::default();
+        for idx in 0..64 {
+            let entity = master.create_entity();
+            master.add_component(entity, Position(idx));
+            master.add_component(entity, Velocity(1));
+            if idx % 3 == 0 {
+                master.add_component(entity, Mass(idx));
+            }
+        }
+        master.insert_resource(Total(0));
+        master.add_system(integrate);
+        master.add_system(weigh);
+        master.run();
+
+        let before = ALLOCATIONS.with(Cell::get);
+        master.run();
+        assert_eq!(ALLOCATIONS.with(Cell::get), before);
+    }
+
+    #[test]
+    fn multithreaded_steady_state_run_does_not_allocate() {
+        fn integrate(query: QueryMut<(&mut Position, &Velocity)>) {
+            for (mut pos, vel) in query {
+                pos.0 += vel.0;
+            }
+        }
+
+        fn weigh(query: Query<(&Mass,)>) {
+            assert!(query.iter().all(|(mass,)| mass.0 >= 0));
+        }
+
+        fn count(query: Query<(&Velocity,)>) {
+            assert_eq!(query.iter().count(), 64);
+        }
+
+        let mut master = Master::default();
+        master.set_executor(Executor::MultiThreaded);
+        for idx in 0..64 {
+            let entity = master.create_entity();
+            master.add_component(entity, Position(idx));
+            master.add_component(entity, Velocity(1));
+            master.add_component(entity, Mass(idx));
+        }
+        master.add_system(integrate);
+        master.add_system(weigh);
+        master.add_system(count);
+        master.run();
+        master.run();
+
+        let before = ALLOCATIONS.with(Cell::get);
+        master.run();
+        assert_eq!(ALLOCATIONS.with(Cell::get), before);
+    }
+
+    #[test]
+    fn resources_reach_systems() {
+        impl Resource for Gravity {}
+        struct Gravity(i32);
+
+        impl Resource for Steps {}
+        struct Steps(usize);
+
+        fn fall(gravity: Res<Gravity>, mut steps: ResMut<Steps>, query: QueryMut<Velocity>) {
+            for mut vel in query {
+                vel.0 += gravity.0;
+            }
+            steps.0 += 1;
+        }
+
+        let mut master = Master::default();
+        let entity = master.create_entity();
+        master.add_component(entity, Velocity(0));
+        master.insert_resource(Gravity(-10));
+        master.insert_resource(Steps(0));
+        master.add_system(fall);
+        master.run();
+        master.run();
+
+        assert_eq!(master.query::<Velocity>().make_singular().0, -20);
+        assert_eq!(master.resource::<Steps>().map(|steps| steps.0), Some(2));
+        assert_eq!(master.remove_resource::<Gravity>().map(|gravity| gravity.0), Some(-10));
+        assert!(!master.contains_resource::<Gravity>());
+    }
+
+    #[test]
+    fn commands_skip_entities_that_died_first() {
+        use crate::arguments::Commands;
+
+        fn despawn(query: Query<(Entity, &Position)>, mut commands: Commands) {
+            for (entity, _) in &query {
+                commands.destroy_entity(entity);
+                commands.add_component(entity, Velocity(0));
+            }
+        }
+        fn despawn_again(query: Query<(Entity, &Position)>, mut commands: Commands) {
+            for (entity, _) in &query {
+                commands.remove_component::<Position>(entity);
+                commands.destroy_entity(entity);
+            }
+        }
+
+        let mut master = Master::default();
+        master.set_executor(Executor::MultiThreaded);
+        let entity = master.spawn(Position(0));
+        master.add_systems((despawn, despawn_again));
+        master.run();
+        assert!(!master.is_alive(entity));
+        assert_eq!(master.query::<Velocity>().iter().count(), 0);
+    }
+
+    #[test]
+    fn commands_apply_after_each_system() {
+        use crate::arguments::Commands;
+
+        fn spawn(mut commands: Commands) {
+            let entity = commands.create_entity();
+            commands.add_component(entity, Position(0));
+        }
+
+        fn count(query: Query<(Entity, &Position)>, mut commands: Commands) {
+            let mut total = 0;
+            for (entity, pos) in &query {
+                total += 1;
+                if pos.0 == 1 {
+                    commands.destroy_entity(entity);
+                }
+            }
+            commands.insert_resource(Total(total));
+        }
+
+        fn age(query: QueryMut<Position>) {
+            for mut pos in query {
+                pos.0 += 1;
+            }
+        }
+
+        impl Resource for Total {}
+        struct Total(i32);
+
+        let mut master = Master::default();
+        master.add_system(spawn);
+        master.add_system(count);
+        master.add_system(age);
+        for expected in [1, 2, 2, 2] {
+            master.run();
+            assert_eq!(master.resource::<Total>().map(|total| total.0), Some(expected));
+        }
+        assert_eq!(master.query::<Position>().iter().count(), 1);
+    }
+
+    #[test]
+    fn multithreaded_executor_orders_conflicting_systems() {
+        use std::thread::ThreadId;
+
+        static LOG: Mutex<Vec<(&str, ThreadId)>> = Mutex::new(Vec::new());
+
+        fn record(name: &'static str) {
+            LOG.lock().unwrap().push((name, thread::current().id()));
+        }
+
+        fn double(query: QueryMut<Position>) {
+            for mut pos in query {
+                pos.0 *= 2;
+            }
+            record("double");
+        }
+
+        fn spin(query: QueryMut<Velocity>, _: Query<Mass>) {
+            for mut vel in query {
+                vel.0 += 1;
+            }
+            record("spin");
+        }
+
+        fn bump(query: QueryMut<Position>, _: Query<Velocity>) {
+            for mut pos in query {
+                pos.0 += 1;
+            }
+            record("bump");
+        }
+
+        let mut master = Master::default();
+        let entity = master.create_entity();
+        master.add_component(entity, Position(1));
+        master.add_component(entity, Velocity(0));
+        master.set_executor(Executor::MultiThreaded);
+        master.add_system(double);
+        master.add_system(spin);
+        master.add_system(bump);
+        master.run();
+
+        assert_eq!(master.query::<Position>().make_singular().0, 3);
+        assert_eq!(master.query::<Velocity>().make_singular().0, 1);
+        let log = LOG.lock().unwrap();
+        let find = |name| log.iter().position(|(other, _)| *other == name).unwrap();
+        assert!(find("double") < find("bump") && find("spin") < find("bump"));
+        assert_ne!(log[find("double")].1, log[find("spin")].1);
+    }
+
+    #[test]
+    fn conflicting_access_is_rejected_on_add() {
+        fn aliased(_: Query<Position>, _: QueryMut<(&Velocity, &mut Position)>) {}
+
+        fn disjoint(_: Query<(&Position, &Velocity)>, _: QueryMut<Mass>, _: Option<Res<Total>>) {}
+
+        fn double_res(_: Res<Total>, _: ResMut<Total>) {}
+
+        impl Resource for Total {}
+        struct Total;
+
+        let mut master = Master::default();
+        assert_eq!(
+            master.try_add_system(aliased),
+            Err(SystemError::ConflictingComponent {
+                system: std::any::type_name_of_val(&aliased),
+                component: type_name::<Position>(),
+            })
+        );
+        assert!(matches!(
+            master.try_add_system(double_res),
+            Err(SystemError::ConflictingResource { resource, .. }) if resource == type_name::<Total>()
+        ));
+        assert!(master.try_add_system(disjoint).is_ok());
+        assert_eq!(master.systems().count(), 1);
+    }
+
+    #[test]
+    fn system_ids_track_registered_systems() {
+        fn push<const N: i32>(query: QueryMut<Position>) {
+            for mut pos in query {
+                pos.0 += N;
+            }
+        }
+
+        let mut master = Master::default();
+        let entity = master.create_entity();
+        master.add_component(entity, Position(0));
+        let first = master.add_system(push::<1>);
+        let second = master.add_system(push::<1>);
+        let third = master.add_system(push::<10>);
+        assert_ne!(first, second);
+        master.run();
+        assert_eq!(master.query::<Position>().make_singular().0, 12);
+
+        assert!(master.remove_system(second));
+        assert!(!master.remove_system(second));
+        assert_eq!(master.replace_system(second, push::<5>), Err(SystemError::UnknownSystem(second)));
+        assert_eq!(master.replace_system(third, push::<100>), Ok(()));
+        master.run();
+        assert_eq!(master.query::<Position>().make_singular().0, 113);
+
+        let names: Vec<(SystemId, &str)> = master.systems().collect();
+        assert_eq!(
+            names,
+            vec![
+                (first, std::any::type_name_of_val(&push::<1>)),
+                (third, std::any::type_name_of_val(&push::<100>))
+            ]
+        );
+    }
+
+    #[test]
+    fn stages_run_in_order_and_startup_runs_once() {
+        use crate::arguments::Commands;
+
+        impl Resource for Trace {}
+        struct Trace(Vec<char>);
+
+        fn setup(mut commands: Commands) {
+            commands.insert_resource(Trace(vec!['s']));
+        }
+
+        fn stage<const NAME: char>(mut trace: ResMut<Trace>) {
+            trace.0.push(NAME);
+        }
+
+        let mut master = Master::default();
+        master.add_system_to_stage(Stage::PostUpdate, stage::<'o'>);
+        master.add_system(stage::<'u'>);
+        master.add_system_to_stage(Stage::PreUpdate, stage::<'p'>);
+        master.add_startup_system(setup);
+        master.add_stage_after(Stage::Update, Stage::Custom("physics"));
+        master.add_system_to_stage(Stage::Custom("physics"), stage::<'x'>);
+        assert_eq!(
+            master.try_add_system_to_stage(Stage::Custom("missing"), stage::<'m'>),
+            Err(SystemError::UnknownStage(Stage::Custom("missing")))
+        );
+        assert_eq!(
+            master.stages().collect::<Vec<_>>(),
+            vec![
+                Stage::Startup,
+                Stage::PreUpdate,
+                Stage::FixedUpdate,
+                Stage::Update,
+                Stage::Custom("physics"),
+                Stage::PostUpdate
+            ]
+        );
+
+        master.run();
+        master.run();
+        assert_eq!(master.resource::<Trace>().unwrap().0, vec!['s', 'p', 'u', 'x', 'o', 'p', 'u', 'x', 'o']);
+    }
+
+    #[test]
+    fn explicit_ordering_overrides_insertion_order() {
+        #[derive(Default)]
+        struct Log(Vec<&'static str>);
+        impl Resource for Log {}
+
+        fn first(mut log: ResMut<Log>) {
+            log.0.push("first");
+        }
+        fn second(mut log: ResMut<Log>) {
+            log.0.push("second");
+        }
+        fn third(mut log: ResMut<Log>) {
+            log.0.push("third");
+        }
+        fn fourth(mut log: ResMut<Log>) {
+            log.0.push("fourth");
+        }
+
+        for executor in [Executor::SingleThreaded, Executor::MultiThreaded] {
+            let mut master = Master::default();
+            master.set_executor(executor);
+            master.insert_resource(Log::default());
+            master.add_system(third.after(second));
+            master.add_system(first.before(second));
+            master.add_systems((second, fourth).chain());
+            master.run();
+            assert_eq!(master.resource::<Log>().unwrap().0, ["first", "second", "third", "fourth"]);
+        }
+    }
+
+    #[test]
+    fn ordering_cycles_are_reported() {
+        fn ping() {}
+        fn pong() {}
+        fn pang() {}
+
+        let mut master = Master::default();
+        master.add_system(ping.after(pang));
+        master.add_systems((pong.after(ping), pang).chain());
+        let Err(ScheduleError::Cycle { stage, systems }) = master.build_schedule() else {
+            panic!("expected a cycle");
+        };
+        assert_eq!(stage, Stage::Update);
+        assert_eq!(systems.first(), systems.last());
+        for name in ["ping", "pong", "pang"] {
+            assert!(systems.iter().any(|system| system.ends_with(name)));
+        }
+    }
+
+    #[test]
+    fn run_conditions_skip_systems_without_fetching() {
+        struct Paused;
+        impl Resource for Paused {}
+
+        #[derive(Default)]
+        struct Frames(i32);
+        impl Resource for Frames {}
+
+        fn paused(paused: Option<Res<Paused>>) -> bool {
+            paused.is_some()
+        }
+        fn running(paused: Option<Res<Paused>>) -> bool {
+            paused.is_none()
+        }
+        fn needs_paused(_: Res<Paused>, mut frames: ResMut<Frames>) {
+            frames.0 += 100;
+        }
+        fn count(mut frames: ResMut<Frames>) {
+            frames.0 += 1;
+        }
+        fn double(mut frames: ResMut<Frames>) {
+            frames.0 *= 2;
+        }
+
+        let mut master = Master::default();
+        master.insert_resource(Frames::default());
+        master.add_system(needs_paused.run_if(paused));
+        master.add_systems((count, double).chain().run_if(running));
+        master.run();
+        master.run();
+        assert_eq!(master.resource::<Frames>().unwrap().0, 6);
+
+        master.insert_resource(Paused);
+        master.run();
+        assert_eq!(master.resource::<Frames>().unwrap().0, 106);
+    }
+
+    #[test]
+    fn group_conditions_are_evaluated_once_per_stage_run() {
+        struct Enabled(bool);
+        impl Resource for Enabled {}
+
+        #[derive(Default)]
+        struct Runs(Vec<&'static str>);
+        impl Resource for Runs {}
+
+        fn enabled(enabled: Res<Enabled>) -> bool {
+            enabled.0
+        }
+        fn disable(mut enabled: ResMut<Enabled>, mut runs: ResMut<Runs>) {
+            enabled.0 = false;
+            runs.0.push("disable");
+        }
+        fn follow(mut runs: ResMut<Runs>) {
+            runs.0.push("follow");
+        }
+
+        let mut master = Master::default();
+        master.insert_resource(Enabled(true));
+        master.insert_resource(Runs::default());
+        master.add_systems((disable, follow).chain().run_if(enabled));
+        master.run();
+        master.run();
+        assert_eq!(master.resource::<Runs>().unwrap().0, ["disable", "follow"]);
+
+        master.resource_mut::<Enabled>().unwrap().0 = true;
+        master.run();
+        assert_eq!(master.resource::<Runs>().unwrap().0.len(), 4);
+    }
+
+    #[test]
+    fn fixed_update_follows_accumulated_time() {
+        #[derive(Default)]
+        struct Steps(u32, Duration);
+        impl Resource for Steps {}
+
+        fn step(mut steps: ResMut<Steps>, time: Res<Time>) {
+            steps.0 += 1;
+            steps.1 = time.delta();
+        }
+
+        let mut master = Master::default();
+        master.set_clock(Clock::Manual(Duration::from_millis(10)));
+        master.insert_resource(FixedTime::new(Duration::from_millis(25)));
+        master.insert_resource(Steps::default());
+        master.add_system_to_stage(Stage::FixedUpdate, step);
+
+        let mut counts = Vec::new();
+        for _ in 0..5 {
+            master.run();
+            counts.push(master.resource::<Steps>().unwrap().0);
+        }
+        assert_eq!(counts, [0, 0, 1, 1, 2]);
+        assert_eq!(master.resource::<Steps>().unwrap().1, Duration::from_millis(25));
+        assert_eq!(master.resource::<Time>().unwrap().delta(), Duration::from_millis(10));
+        assert_eq!(master.resource::<FixedTime>().unwrap().accumulated(), Duration::ZERO);
+
+        master.resource_mut::<Time>().unwrap().set_scale(3.0);
+        master.run();
+        assert_eq!(master.resource::<Steps>().unwrap().0, 3);
+
+        master.resource_mut::<Time>().unwrap().pause();
+        master.set_clock(Clock::Manual(Duration::from_secs(1)));
+        master.run();
+        assert_eq!(master.resource::<Steps>().unwrap().0, 3);
+
+        let time = master.resource::<Time>().unwrap();
+        assert_eq!(time.frame_count(), 7);
+        assert_eq!(time.delta(), Duration::ZERO);
+        assert_eq!(time.elapsed(), Duration::from_millis(80));
+    }
+
+    #[test]
+    fn fixed_update_steps_per_frame_are_capped() {
+        #[derive(Default)]
+        struct Steps(u32);
+        impl Resource for Steps {}
+
+        fn step(mut steps: ResMut<Steps>) {
+            steps.0 += 1;
+        }
+
+        let mut master = Master::default();
+        master.set_clock(Clock::Manual(Duration::from_secs(10)));
+        master.insert_resource(FixedTime::new(Duration::from_millis(10)));
+        master.insert_resource(Steps::default());
+        master.add_system_to_stage(Stage::FixedUpdate, step);
+
+        master.run();
+        assert_eq!(master.resource::<Steps>().unwrap().0, 8);
+        assert_eq!(master.resource::<FixedTime>().unwrap().accumulated(), Duration::ZERO);
+
+        master.resource_mut::<FixedTime>().unwrap().set_max_steps(3);
+        master.run();
+        assert_eq!(master.resource::<Steps>().unwrap().0, 11);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not registered")]
+    fn sending_unregistered_events_panics() {
+        struct Ping;
+        impl Event for Ping {}
+
+        Master::default().send_event(Ping);
+    }
+
+    #[test]
+    fn events_reach_every_reader_and_expire_after_two_updates() {
+        use crate::events::{EventReader, EventWriter};
+
+        struct Collision(i32);
+        impl Event for Collision {}
+
+        #[derive(Default)]
+        struct Seen(Vec<(char, i32)>);
+        impl Resource for Seen {}
+
+        fn collide(mut writer: EventWriter<Collision>, time: Res<Time>) {
+            if time.frame_count() == 1 {
+                writer.send_batch([Collision(1), Collision(2)]);
+            }
+        }
+        fn early(mut reader: EventReader<Collision>, mut seen: ResMut<Seen>) {
+            seen.0.extend(reader.read().map(|Collision(idx)| ('e', *idx)));
+        }
+        fn late(mut reader: EventReader<Collision>, mut seen: ResMut<Seen>) {
+            seen.0.extend(reader.read().map(|Collision(idx)| ('l', *idx)));
+        }
+        fn second_frame(time: Res<Time>) -> bool {
+            time.frame_count() >= 2
+        }
+
+        let mut master = Master::default();
+        master.add_event::<Collision>();
+        master.insert_resource(Seen::default());
+        master.add_system_to_stage(Stage::PreUpdate, early);
+        master.add_system(collide);
+        master.add_system_to_stage(Stage::PostUpdate, late.run_if(second_frame));
+
+        master.run();
+        assert_eq!(master.resource::<Seen>().unwrap().0, []);
+        master.send_event(Collision(3));
+        master.run();
+        let expected = [('e', 1), ('e', 2), ('e', 3), ('l', 1), ('l', 2), ('l', 3)];
+        assert_eq!(master.resource::<Seen>().unwrap().0, expected);
+        assert_eq!(master.resource::<Events<Collision>>().unwrap().len(), 3);
+        master.run();
+        master.run();
+        assert_eq!(master.resource::<Seen>().unwrap().0, expected);
+        assert!(master.resource::<Events<Collision>>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn added_and_changed_filters_track_writes_since_last_run() {
+        use crate::arguments::{Added, Changed};
+
+        #[derive(Default)]
+        struct Seen(Vec<(Vec<Entity>, Vec<Entity>)>);
+        impl Resource for Seen {}
+
+        fn drift(mut bodies: QueryMut<(&mut Position, &Velocity)>) {
+            for (mut pos, vel) in bodies.iter_mut() {
+                if vel.0 != 0 {
+                    pos.0 += vel.0;
+                }
+            }
+        }
+        fn watch(
+            added: Query<Entity, Added<Position>>,
+            changed: Query<Entity, Changed<Position>>,
+            mut seen: ResMut<Seen>,
+        ) {
+            let mut added: Vec<Entity> = added.iter().collect();
+            let mut changed: Vec<Entity> = changed.iter().collect();
+            added.sort();
+            changed.sort();
+            seen.0.push((added, changed));
+        }
+
+        let mut master = Master::default();
+        master.insert_resource(Seen::default());
+        master.add_system_to_stage(Stage::PreUpdate, drift);
+        master.add_system(watch);
+        let moving = master.create_entity();
+        master.add_component(moving, Position(0));
+        master.add_component(moving, Velocity(1));
+        let still = master.create_entity();
+        master.add_component(still, Position(0));
+        master.add_component(still, Velocity(0));
+
+        master.run();
+        master.run();
+        let late = master.create_entity();
+        master.add_component(late, Position(0));
+        master.add_component(still, Position(5));
+        master.run();
+
+        let seen = &master.resource::<Seen>().unwrap().0;
+        assert_eq!(seen[0], (vec![moving, still], vec![moving, still]));
+        assert_eq!(seen[1], (vec![], vec![moving]));
+        assert_eq!(seen[2], (vec![late], vec![moving, still, late]));
+    }
+
+    #[test]
+    fn systems_do_not_see_their_own_writes_as_changes() {
+        use crate::arguments::Changed;
+
+        #[derive(Default)]
+        struct Hits(Vec<usize>);
+        impl Resource for Hits {}
+
+        fn query_first(
+            changed: Query<Entity, Changed<Position>>,
+            mut pos: QueryMut<Position>,
+            mut hits: ResMut<Hits>,
+        ) {
+            hits.0.push(changed.iter().count());
+            for mut pos in pos.iter_mut() {
+                pos.0 += 1;
+            }
+        }
+        fn query_last(
+            mut pos: QueryMut<Position>,
+            mut hits: ResMut<Hits>,
+            changed: Query<Entity, Changed<Position>>,
+        ) {
+            hits.0.push(changed.iter().count());
+            for mut pos in pos.iter_mut() {
+                pos.0 += 1;
+            }
+        }
+
+        let mut first = Master::default();
+        first.insert_resource(Hits::default());
+        first.spawn(Position(0));
+        first.add_system(query_first);
+        let mut last = Master::default();
+        last.insert_resource(Hits::default());
+        last.spawn(Position(0));
+        last.add_system(query_last);
+        for _ in 0..4 {
+            first.run();
+            last.run();
+        }
+        assert_eq!(first.resource::<Hits>().unwrap().0, [1, 0, 0, 0]);
+        assert_eq!(last.resource::<Hits>().unwrap().0, [1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn removed_components_report_removals_and_despawns_once() {
+        use crate::arguments::RemovedComponents;
+
+        impl Component for Socket {
+            const STORAGE: StorageType = StorageType::SparseSet;
+        }
+        struct Socket;
+
+        #[derive(Default)]
+        struct Freed(Vec<Vec<Entity>>);
+        impl Resource for Freed {}
+
+        fn free(
+            positions: RemovedComponents<Position>,
+            sockets: RemovedComponents<Socket>,
+            mut freed: ResMut<Freed>,
+        ) {
+            let mut entities: Vec<Entity> = positions.iter().chain(&sockets).collect();
+            entities.sort();
+            freed.0.push(entities);
+        }
+
+        let mut master = Master::default();
+        master.insert_resource(Freed::default());
+        master.add_system(free);
+        let entities: Vec<Entity> = (0..3)
+            .map(|idx| {
+                let entity = master.create_entity();
+                master.add_component(entity, Position(idx));
+                master.add_component(entity, Socket);
+                entity
+            })
+            .collect();
+
+        master.run();
+        master.remove_component::<Position>(entities[0]);
+        master.remove_component::<Position>(entities[0]);
+        master.destroy_entity(entities[1]);
+        master.run();
+        master.run();
+
+        master.run();
+        let freed = &master.resource::<Freed>().unwrap().0;
+        assert_eq!(freed[0], []);
+        assert_eq!(freed[1], [entities[0], entities[1], entities[1]]);
+        assert_eq!(freed[2], []);
+        assert!(master.components.removed::<Position>().is_empty());
+    }
+
+    #[test]
+    fn lifecycle_hooks_fire_around_insert_replace_and_remove() {
+        #[derive(Default)]
+        struct Hooks(Vec<(&'static str, i32)>);
+        impl Resource for Hooks {}
+
+        struct Tracked(i32);
+        impl Component for Tracked {
+            fn on_add(world: &mut World, entity: Entity) {
+                log(world, entity, "add");
+            }
+
+            fn on_insert(world: &mut World, entity: Entity) {
+                log(world, entity, "insert");
+            }
+
+            fn on_replace(world: &mut World, entity: Entity) {
+                log(world, entity, "replace");
+            }
+
+            fn on_remove(world: &mut World, entity: Entity) {
+                log(world, entity, "remove");
+            }
+        }
+
+        fn log(world: &mut World, entity: Entity, hook: &'static str) {
+            let value = world.query::<Tracked>().get(entity).unwrap().0;
+            world.resource_mut::<Hooks>().unwrap().0.push((hook, value));
+        }
+
+        let mut world = World::default();
+        world.insert_resource(Hooks::default());
+        let entity = world.create_entity();
+        world.add_component(entity, Tracked(1));
+        world.add_component(entity, Tracked(2));
+        world.remove_component::<Tracked>(entity);
+        world.remove_component::<Tracked>(entity);
+        world.add_component(entity, Tracked(3));
+        world.destroy_entity(entity);
+
+        assert_eq!(
+            world.resource::<Hooks>().unwrap().0,
+            [
+                ("add", 1),
+                ("insert", 1),
+                ("replace", 1),
+                ("insert", 2),
+                ("replace", 2),
+                ("remove", 2),
+                ("add", 3),
+                ("insert", 3),
+                ("replace", 3),
+                ("remove", 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_compose_with_tuples_or_and_not() {
+        use crate::arguments::{Not, Or, With, Without};
+
+        impl Component for Disabled {
+            const STORAGE: StorageType = StorageType::SparseSet;
+        }
+        struct Disabled;
+
+        let mut master = Master::default();
+        let entities: Vec<Entity> = (0..6)
+            .map(|idx| {
+                let entity = master.create_entity();
+                master.add_component(entity, Position(idx));
+                if idx % 2 == 0 {
+                    master.add_component(entity, Velocity(idx));
+                }
+                if idx % 3 == 0 {
+                    master.add_component(entity, Mass(idx));
+                }
+                if idx >= 4 {
+                    master.add_component(entity, Disabled);
+                }
+                entity
+            })
+            .collect();
+        let pick = |picked: &[usize]| picked.iter().map(|idx| entities[*idx]).collect::<Vec<_>>();
+        let ticks = master.components.current_ticks();
+
+        let moving = Query::<Entity, (With<Velocity>, Without<Mass>, Without<Disabled>)>::new(
+            &master.components,
+            ticks,
+        );
+        assert_eq!(moving.iter().collect::<Vec<_>>(), pick(&[2]));
+
+        let mut either: Vec<Entity> =
+            Query::<Entity, Or<(With<Mass>, With<Disabled>)>>::new(&master.components, ticks)
+                .iter()
+                .collect();
+        either.sort();
+        assert_eq!(either, pick(&[0, 3, 4, 5]));
+
+        let mut enabled: Vec<Entity> =
+            Query::<Entity, (Not<With<Disabled>>, Not<Without<Position>>)>::new(&master.components, ticks)
+                .iter()
+                .collect();
+        enabled.sort();
+        assert_eq!(enabled, pick(&[0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn optional_fetches_visit_entities_without_the_component() {
+        let mut master = Master::default();
+        for idx in 0..4 {
+            let entity = master.create_entity();
+            master.add_component(entity, Position(idx));
+            if idx % 2 == 1 {
+                master.add_component(entity, Velocity(idx * 10));
+            }
+        }
+
+        for (pos, vel) in master.query_mut::<(&Position, Option<&mut Velocity>)>() {
+            if let Some(mut vel) = vel {
+                vel.0 += pos.0;
+            }
+        }
+        let mut seen: Vec<(i32, Option<i32>)> = master
+            .query::<(&Position, Option<&Velocity>)>()
+            .iter()
+            .map(|(pos, vel)| (pos.0, vel.map(|vel| vel.0)))
+            .collect();
+        seen.sort();
+        assert_eq!(seen, [(0, None), (1, Some(11)), (2, None), (3, Some(33))]);
+    }
+
+    #[test]
+    fn bundles_move_storage_once() {
+        impl Component for Tag {
+            const STORAGE: StorageType = StorageType::SparseSet;
+        }
+        struct Tag;
+
+        let mut master = Master::default();
+        let entity = master.spawn((Position(1), (Velocity(2), Mass(3))));
+        assert_eq!(master.components.archetypes().len(), 2);
+
+        master.insert_bundle(entity, (Mass(30), Tag));
+        assert_eq!(master.components.archetypes().len(), 2);
+        assert_eq!(master.query::<(&Position, &Velocity, &Mass)>().iter().count(), 1);
+        assert_eq!(master.query::<Mass>().get(entity).unwrap().0, 30);
+        assert!(master.query::<Tag>().contains(entity));
+
+        master.remove_bundle::<(Velocity, Mass, Tag)>(entity);
+        assert_eq!(master.components.archetypes().len(), 3);
+        assert_eq!(
+            master.components.locate(entity).unwrap().0.type_names().collect::<Vec<_>>(),
+            [type_name::<Position>()]
+        );
+        assert!(!master.query::<Tag>().contains(entity));
+    }
+
+    #[test]
+    #[should_panic(expected = "more than once")]
+    fn bundles_reject_duplicate_components() {
+        let mut master = Master::default();
+        master.spawn((Position(1), Position(2)));
+    }
+
+    #[test]
+    fn entity_accessors_read_and_write_single_entities() {
+        impl Component for Tag {
+            const STORAGE: StorageType = StorageType::SparseSet;
+        }
+        struct Tag;
+
+        let mut master = Master::default();
+        let entity = master.spawn((Position(1), Tag));
+
+        let entity_ref = master.entity(entity);
+        assert_eq!(entity_ref.get::<Position>().unwrap().0, 1);
+        assert!(entity_ref.contains::<Tag>());
+        assert!(entity_ref.get::<Velocity>().is_none());
+        let mut names = entity_ref.component_names();
+        names.sort();
+        let mut expected = [type_name::<Position>(), type_name::<Tag>()];
+        expected.sort();
+        assert_eq!(names, expected);
+        assert!(entity_ref.component_ids().contains(&TypeId::of::<Tag>()));
+
+        let mut entity_mut = master.entity_mut(entity);
+        entity_mut.get_mut::<Position>().unwrap().0 += 10;
+        entity_mut.insert(Velocity(2));
+        assert_eq!(entity_mut.remove::<Position>(), Some(Position(11)));
+        assert_eq!(entity_mut.remove::<Position>(), None);
+        assert_eq!(entity_mut.get::<Velocity>().unwrap().0, 2);
+        entity_mut.despawn();
+        assert!(!master.is_alive(entity));
+    }
+
+    #[test]
+    fn removed_and_replaced_components_are_handed_back() {
+        let mut master = Master::default();
+        let template = master.spawn((Position(1), Velocity(2)));
+        let instance = master.spawn(Mass(3));
+
+        assert_eq!(master.replace_component(template, Position(10)), Some(Position(1)));
+        assert_eq!(master.replace_component(instance, Position(20)), None);
+
+        let velocity = master.take_component::<Velocity>(template);
+        master.add_component(instance, velocity);
+        assert_eq!(master.query::<Velocity>().get(instance).unwrap().0, 2);
+        assert!(!master.query::<Velocity>().contains(template));
+
+        assert_eq!(master.remove_component::<Position>(template), Some(Position(10)));
+        assert_eq!(master.remove_component::<Position>(template), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "has no component")]
+    fn taking_a_missing_component_panics() {
+        let mut master = Master::default();
+        let entity = master.spawn(Position(0));
+        master.take_component::<Velocity>(entity);
+    }
+
+    #[test]
+    fn destroyed_entities_are_recycled_with_new_generation() {
+        let mut master = Master::default();
+        let first = master.create_entity();
+        master.add_component(first, Position(1));
+        master.destroy_entity(first);
+        assert!(!master.is_alive(first));
+
+        let second = master.create_entity();
+        assert_eq!(*second, *first);
+        assert_eq!(second.generation(), first.generation() + 1);
+        assert!(master.is_alive(second));
+        assert!(master.query::<Position>().get(second).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "is not alive")]
+    fn stale_entity_handles_panic() {
+        let mut master = Master::default();
+        let entity = master.create_entity();
+        master.destroy_entity(entity);
+        master.create_entity();
+        master.add_component(entity, Position(0));
+    }
+}
@@ -0,0 +1,757 @@
+use std::{
+    any::{Any, TypeId, type_name},
+    collections::BTreeSet,
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock, PoisonError},
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    Executor, System, World,
+    systems::{Access, Condition, SystemBuilder, SystemError, SystemId},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Startup,
+    PreUpdate,
+    FixedUpdate,
+    Update,
+    PostUpdate,
+    Custom(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SystemLabel {
+    id: TypeId,
+    name: &'static str,
+}
+
+impl SystemLabel {
+    pub fn of<T>() -> Self
+    where
+        T: 'static,
+    {
+        Self { id: TypeId::of::<T>(), name: type_name::<T>() }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Constraints {
+    before: Vec<SystemLabel>,
+    after: Vec<SystemLabel>,
+}
+
+pub struct Ordered<S> {
+    system: S,
+    constraints: Constraints,
+}
+
+impl<S> Ordered<S> {
+    pub(crate) fn new(system: S) -> Self {
+        Self { system, constraints: Constraints::default() }
+    }
+
+    pub(crate) fn run_before(mut self, label: SystemLabel) -> Self {
+        self.constraints.before.push(label);
+        self
+    }
+
+    pub(crate) fn run_after(mut self, label: SystemLabel) -> Self {
+        self.constraints.after.push(label);
+        self
+    }
+}
+
+impl<S> System for Ordered<S>
+where
+    S: System,
+{
+    fn run(&mut self, world: &World) {
+        self.system.run(world);
+    }
+
+    fn apply(&mut self, world: &mut World) {
+        self.system.apply(world);
+    }
+
+    fn name(&self) -> &'static str {
+        self.system.name()
+    }
+
+    fn label(&self) -> SystemLabel {
+        self.system.label()
+    }
+
+    fn access(&self) -> Access {
+        self.system.access()
+    }
+
+    fn constraints(&self, constraints: &mut Constraints) {
+        self.system.constraints(constraints);
+        constraints.before.extend_from_slice(&self.constraints.before);
+        constraints.after.extend_from_slice(&self.constraints.after);
+    }
+}
+
+impl<S> SystemBuilder<Ordered<S>> for Ordered<S>
+where
+    S: System,
+{
+    type System = Self;
+
+    fn build_system(self) -> Self::System {
+        self
+    }
+}
+
+pub struct RunIf<S, C> {
+    system: S,
+    condition: C,
+    ran: bool,
+}
+
+impl<S, C> RunIf<S, C> {
+    pub(crate) fn new(system: S, condition: C) -> Self {
+        Self { system, condition, ran: false }
+    }
+}
+
+impl<S, C> System for RunIf<S, C>
+where
+    S: System,
+    C: Condition,
+{
+    fn run(&mut self, world: &World) {
+        self.ran = self.condition.evaluate(world);
+        if self.ran {
+            self.system.run(world);
+        }
+    }
+
+    fn apply(&mut self, world: &mut World) {
+        if self.ran {
+            self.system.apply(world);
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        self.system.name()
+    }
+
+    fn label(&self) -> SystemLabel {
+        self.system.label()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = self.system.access();
+        access.extend(&self.condition.access());
+        access
+    }
+
+    fn constraints(&self, constraints: &mut Constraints) {
+        self.system.constraints(constraints);
+    }
+
+    fn validate(&self) -> Result<(), SystemError> {
+        self.system.validate()?;
+        self.condition.access().validate(self.condition.name())
+    }
+}
+
+impl<S, C> SystemBuilder<RunIf<S, C>> for RunIf<S, C>
+where
+    S: System,
+    C: Condition,
+{
+    type System = Self;
+
+    fn build_system(self) -> Self::System {
+        self
+    }
+}
+
+pub trait SystemGroup<Marker> {
+    fn build_systems(self, systems: &mut Vec<Box<dyn System>>);
+
+    fn chained(&self) -> bool {
+        false
+    }
+
+    fn chain(self) -> Chain<Self>
+    where
+        Self: Sized,
+    {
+        Chain(self)
+    }
+
+    fn run_if<C, M>(self, condition: C) -> RunIfGroup<Self, C>
+    where
+        Self: Sized,
+        C: SystemBuilder<M>,
+        C::System: Condition + 'static,
+    {
+        RunIfGroup { group: self, condition }
+    }
+}
+
+pub struct Chain<G>(G);
+
+impl<G, M> SystemGroup<Chain<M>> for Chain<G>
+where
+    G: SystemGroup<M>,
+{
+    fn build_systems(self, systems: &mut Vec<Box<dyn System>>) {
+        self.0.build_systems(systems);
+    }
+
+    fn chained(&self) -> bool {
+        true
+    }
+}
+
+pub struct RunIfGroup<G, C> {
+    group: G,
+    condition: C,
+}
+
+impl<G, C, M, MC> SystemGroup<RunIfGroup<M, MC>> for RunIfGroup<G, C>
+where
+    G: SystemGroup<M>,
+    C: SystemBuilder<MC>,
+    C::System: Condition + 'static,
+{
+    fn build_systems(self, systems: &mut Vec<Box<dyn System>>) {
+        let mut inner = Vec::new();
+        self.group.build_systems(&mut inner);
+        let shared = Arc::new(Mutex::new(SharedCondition {
+            condition: self.condition.build_system(),
+            members: 0,
+            pending: 0,
+            result: false,
+        }));
+        for system in inner {
+            systems.push(Box::new(RunIf::new(system, GroupCondition::new(&shared))));
+        }
+    }
+
+    fn chained(&self) -> bool {
+        self.group.chained()
+    }
+}
+
+struct SharedCondition<C> {
+    condition: C,
+    members: usize,
+    pending: usize,
+    result: bool,
+}
+
+// Every member of a gated group holds one of these. The first member to run in a stage pass
+// evaluates the condition and the rest reuse that result, so the group runs all-or-nothing.
+struct GroupCondition<C> {
+    shared: Arc<Mutex<SharedCondition<C>>>,
+}
+
+impl<C> GroupCondition<C> {
+    fn new(shared: &Arc<Mutex<SharedCondition<C>>>) -> Self {
+        let shared = Arc::clone(shared);
+        shared.lock().unwrap().members += 1;
+        Self { shared }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SharedCondition<C>> {
+        self.shared.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<C> Condition for GroupCondition<C>
+where
+    C: Condition,
+{
+    fn evaluate(&mut self, world: &World) -> bool {
+        let mut shared = self.lock();
+        if shared.pending == 0 {
+            shared.result = shared.condition.evaluate(world);
+            shared.pending = shared.members;
+        }
+        shared.pending -= 1;
+        shared.result
+    }
+
+    fn name(&self) -> &'static str {
+        self.lock().condition.name()
+    }
+
+    fn access(&self) -> Access {
+        self.lock().condition.access()
+    }
+}
+
+impl<C> Drop for GroupCondition<C> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        shared.members -= 1;
+        shared.pending = 0;
+    }
+}
+
+impl<A, MA> SystemGroup<(MA,)> for (A,)
+where
+    A: SystemBuilder<MA>,
+    A::System: System + 'static,
+{
+    fn build_systems(self, systems: &mut Vec<Box<dyn System>>) {
+        systems.push(Box::new(self.0.build_system()));
+    }
+}
+
+impl<A, MA, B, MB> SystemGroup<(MA, MB)> for (A, B)
+where
+    A: SystemBuilder<MA>,
+    A::System: System + 'static,
+    B: SystemBuilder<MB>,
+    B::System: System + 'static,
+{
+    fn build_systems(self, systems: &mut Vec<Box<dyn System>>) {
+        systems.push(Box::new(self.0.build_system()));
+        systems.push(Box::new(self.1.build_system()));
+    }
+}
+
+impl<A, MA, B, MB, C, MC> SystemGroup<(MA, MB, MC)> for (A, B, C)
+where
+    A: SystemBuilder<MA>,
+    A::System: System + 'static,
+    B: SystemBuilder<MB>,
+    B::System: System + 'static,
+    C: SystemBuilder<MC>,
+    C::System: System + 'static,
+{
+    fn build_systems(self, systems: &mut Vec<Box<dyn System>>) {
+        systems.push(Box::new(self.0.build_system()));
+        systems.push(Box::new(self.1.build_system()));
+        systems.push(Box::new(self.2.build_system()));
+    }
+}
+
+impl<A, MA, B, MB, C, MC, D, MD> SystemGroup<(MA, MB, MC, MD)> for (A, B, C, D)
+where
+    A: SystemBuilder<MA>,
+    A::System: System + 'static,
+    B: SystemBuilder<MB>,
+    B::System: System + 'static,
+    C: SystemBuilder<MC>,
+    C::System: System + 'static,
+    D: SystemBuilder<MD>,
+    D::System: System + 'static,
+{
+    fn build_systems(self, systems: &mut Vec<Box<dyn System>>) {
+        systems.push(Box::new(self.0.build_system()));
+        systems.push(Box::new(self.1.build_system()));
+        systems.push(Box::new(self.2.build_system()));
+        systems.push(Box::new(self.3.build_system()));
+    }
+}
+
+impl<A, MA, B, MB, C, MC, D, MD, E, ME> SystemGroup<(MA, MB, MC, MD, ME)> for (A, B, C, D, E)
+where
+    A: SystemBuilder<MA>,
+    A::System: System + 'static,
+    B: SystemBuilder<MB>,
+    B::System: System + 'static,
+    C: SystemBuilder<MC>,
+    C::System: System + 'static,
+    D: SystemBuilder<MD>,
+    D::System: System + 'static,
+    E: SystemBuilder<ME>,
+    E::System: System + 'static,
+{
+    fn build_systems(self, systems: &mut Vec<Box<dyn System>>) {
+        systems.push(Box::new(self.0.build_system()));
+        systems.push(Box::new(self.1.build_system()));
+        systems.push(Box::new(self.2.build_system()));
+        systems.push(Box::new(self.3.build_system()));
+        systems.push(Box::new(self.4.build_system()));
+    }
+}
+
+impl<A, MA, B, MB, C, MC, D, MD, E, ME, G, MG> SystemGroup<(MA, MB, MC, MD, ME, MG)> for (A, B, C, D, E, G)
+where
+    A: SystemBuilder<MA>,
+    A::System: System + 'static,
+    B: SystemBuilder<MB>,
+    B::System: System + 'static,
+    C: SystemBuilder<MC>,
+    C::System: System + 'static,
+    D: SystemBuilder<MD>,
+    D::System: System + 'static,
+    E: SystemBuilder<ME>,
+    E::System: System + 'static,
+    G: SystemBuilder<MG>,
+    G::System: System + 'static,
+{
+    fn build_systems(self, systems: &mut Vec<Box<dyn System>>) {
+        systems.push(Box::new(self.0.build_system()));
+        systems.push(Box::new(self.1.build_system()));
+        systems.push(Box::new(self.2.build_system()));
+        systems.push(Box::new(self.3.build_system()));
+        systems.push(Box::new(self.4.build_system()));
+        systems.push(Box::new(self.5.build_system()));
+    }
+}
+
+impl<A, MA, B, MB, C, MC, D, MD, E, ME, G, MG, H, MH> SystemGroup<(MA, MB, MC, MD, ME, MG, MH)>
+    for (A, B, C, D, E, G, H)
+where
+    A: SystemBuilder<MA>,
+    A::System: System + 'static,
+    B: SystemBuilder<MB>,
+    B::System: System + 'static,
+    C: SystemBuilder<MC>,
+    C::System: System + 'static,
+    D: SystemBuilder<MD>,
+    D::System: System + 'static,
+    E: SystemBuilder<ME>,
+    E::System: System + 'static,
+    G: SystemBuilder<MG>,
+    G::System: System + 'static,
+    H: SystemBuilder<MH>,
+    H::System: System + 'static,
+{
+    fn build_systems(self, systems: &mut Vec<Box<dyn System>>) {
+        systems.push(Box::new(self.0.build_system()));
+        systems.push(Box::new(self.1.build_system()));
+        systems.push(Box::new(self.2.build_system()));
+        systems.push(Box::new(self.3.build_system()));
+        systems.push(Box::new(self.4.build_system()));
+        systems.push(Box::new(self.5.build_system()));
+        systems.push(Box::new(self.6.build_system()));
+    }
+}
+
+impl<A, MA, B, MB, C, MC, D, MD, E, ME, G, MG, H, MH, J, MJ> SystemGroup<(MA, MB, MC, MD, ME, MG, MH, MJ)>
+    for (A, B, C, D, E, G, H, J)
+where
+    A: SystemBuilder<MA>,
+    A::System: System + 'static,
+    B: SystemBuilder<MB>,
+    B::System: System + 'static,
+    C: SystemBuilder<MC>,
+    C::System: System + 'static,
+    D: SystemBuilder<MD>,
+    D::System: System + 'static,
+    E: SystemBuilder<ME>,
+    E::System: System + 'static,
+    G: SystemBuilder<MG>,
+    G::System: System + 'static,
+    H: SystemBuilder<MH>,
+    H::System: System + 'static,
+    J: SystemBuilder<MJ>,
+    J::System: System + 'static,
+{
+    fn build_systems(self, systems: &mut Vec<Box<dyn System>>) {
+        systems.push(Box::new(self.0.build_system()));
+        systems.push(Box::new(self.1.build_system()));
+        systems.push(Box::new(self.2.build_system()));
+        systems.push(Box::new(self.3.build_system()));
+        systems.push(Box::new(self.4.build_system()));
+        systems.push(Box::new(self.5.build_system()));
+        systems.push(Box::new(self.6.build_system()));
+        systems.push(Box::new(self.7.build_system()));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    Cycle { stage: Stage, systems: Vec<&'static str> },
+}
+
+impl Display for ScheduleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            | ScheduleError::Cycle { stage, systems } => {
+                write!(f, "systems in stage {:?} form an ordering cycle: {}", stage, systems.join(" -> "))
+            }
+        }
+    }
+}
+
+impl Error for ScheduleError {}
+
+pub(crate) struct SystemStage {
+    pub(crate) stage: Stage,
+    pub(crate) systems: Vec<(SystemId, Box<dyn System>)>,
+    pub(crate) chained: Vec<(SystemId, SystemId)>,
+    pub(crate) dirty: bool,
+    dependencies: Vec<Vec<usize>>,
+    waves: Vec<usize>,
+    wave_count: usize,
+}
+
+impl SystemStage {
+    pub(crate) fn new(stage: Stage) -> Self {
+        Self {
+            stage,
+            systems: Vec::new(),
+            chained: Vec::new(),
+            dirty: false,
+            dependencies: Vec::new(),
+            waves: Vec::new(),
+            wave_count: 0,
+        }
+    }
+
+    pub(crate) fn build(&mut self) -> Result<(), ScheduleError> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let count = self.systems.len();
+        let labels: Vec<SystemLabel> = self.systems.iter().map(|(_, system)| system.label()).collect();
+        let position = |id: SystemId| self.systems.iter().position(|(other, _)| *other == id);
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); count];
+        for (idx, (_, system)) in self.systems.iter().enumerate() {
+            let mut constraints = Constraints::default();
+            system.constraints(&mut constraints);
+            for other in (0..count).filter(|other| *other != idx) {
+                if constraints.before.contains(&labels[other]) {
+                    predecessors[other].push(idx);
+                }
+                if constraints.after.contains(&labels[other]) {
+                    predecessors[idx].push(other);
+                }
+            }
+        }
+        for (first, second) in self.chained.iter() {
+            if let (Some(first), Some(second)) = (position(*first), position(*second)) {
+                predecessors[second].push(first);
+            }
+        }
+
+        let mut pending: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+        let mut successors: Vec<Vec<usize>> = vec![Vec::new(); count];
+        for (idx, before) in predecessors.iter().enumerate() {
+            for prev in before {
+                successors[*prev].push(idx);
+            }
+        }
+        let mut ready: BTreeSet<(SystemId, usize)> =
+            (0..count).filter(|idx| pending[*idx] == 0).map(|idx| (self.systems[idx].0, idx)).collect();
+        let mut order = Vec::with_capacity(count);
+        while let Some((_, idx)) = ready.pop_first() {
+            order.push(idx);
+            for next in successors[idx].iter() {
+                pending[*next] -= 1;
+                if pending[*next] == 0 {
+                    ready.insert((self.systems[*next].0, *next));
+                }
+            }
+        }
+        if order.len() < count {
+            return Err(ScheduleError::Cycle {
+                stage: self.stage,
+                systems: self.find_cycle(&predecessors, &pending),
+            });
+        }
+
+        let mut moved: Vec<Option<(SystemId, Box<dyn System>)>> = self.systems.drain(..).map(Some).collect();
+        let mut rank = vec![0; count];
+        for (new, old) in order.iter().enumerate() {
+            rank[*old] = new;
+            self.systems.push(moved[*old].take().unwrap());
+        }
+        self.dependencies =
+            order.iter().map(|old| predecessors[*old].iter().map(|prev| rank[*prev]).collect()).collect();
+        self.build_waves();
+        self.dirty = false;
+        Ok(())
+    }
+
+    fn build_waves(&mut self) {
+        let accesses: Vec<Access> = self.systems.iter().map(|(_, system)| system.access()).collect();
+        self.waves.clear();
+        for (idx, access) in accesses.iter().enumerate() {
+            let wave = (0..idx)
+                .filter(|prev| {
+                    !accesses[*prev].is_compatible(access) || self.dependencies[idx].contains(prev)
+                })
+                .map(|prev| self.waves[prev] + 1)
+                .max()
+                .unwrap_or(0);
+            self.waves.push(wave);
+        }
+        self.wave_count = self.waves.iter().max().map_or(0, |last| last + 1);
+    }
+
+    fn find_cycle(&self, predecessors: &[Vec<usize>], pending: &[usize]) -> Vec<&'static str> {
+        let mut path = vec![(0..pending.len()).find(|idx| pending[*idx] > 0).unwrap()];
+        loop {
+            let current = *path.last().unwrap();
+            let prev = *predecessors[current].iter().find(|prev| pending[**prev] > 0).unwrap();
+            if let Some(start) = path.iter().position(|idx| *idx == prev) {
+                let mut cycle: Vec<&'static str> =
+                    path[start..].iter().rev().map(|idx| self.systems[*idx].1.name()).collect();
+                cycle.push(cycle[0]);
+                return cycle;
+            }
+            path.push(prev);
+        }
+    }
+
+    pub(crate) fn run(&mut self, world: &mut World, executor: Executor, workers: &WorkerPool) {
+        match executor {
+            | Executor::SingleThreaded => {
+                for (_, system) in self.systems.iter_mut() {
+                    system.run(world);
+                    system.apply(world);
+                }
+            }
+            | Executor::MultiThreaded => self.run_parallel(world, workers),
+        }
+    }
+
+    fn run_parallel(&mut self, world: &mut World, workers: &WorkerPool) {
+        for wave in 0..self.wave_count {
+            self.run_wave(wave, world, workers);
+            for system in self.wave_mut(wave) {
+                system.apply(world);
+            }
+        }
+    }
+
+    fn run_wave(&mut self, wave: usize, world: &World, workers: &WorkerPool) {
+        let mut batch = self.wave_mut(wave).peekable();
+        let first = batch.next().unwrap();
+        match batch.peek() {
+            | None => first.run(world),
+            | Some(_) => workers.run(world, first, batch),
+        }
+    }
+
+    fn wave_mut(&mut self, wave: usize) -> impl Iterator<Item = &mut Box<dyn System>> {
+        self.systems
+            .iter_mut()
+            .zip(&self.waves)
+            .filter(move |(_, other)| **other == wave)
+            .map(|((_, system), _)| system)
+    }
+}
+
+struct Job {
+    system: *mut dyn System,
+    world: *const World,
+}
+
+// SAFETY: systems are `Send + Sync`, and `WorkerPool::run` doesn't return until every job it
+// queued has finished, so the pointers never outlive the borrows they were made from.
+unsafe impl Send for Job {}
+
+#[derive(Default)]
+struct PoolState {
+    jobs: Vec<Job>,
+    running: usize,
+    panic: Option<Box<dyn Any + Send>>,
+    shutdown: bool,
+}
+
+#[derive(Default)]
+struct PoolShared {
+    state: Mutex<PoolState>,
+    queued: Condvar,
+    finished: Condvar,
+}
+
+impl PoolShared {
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn work(&self) {
+        loop {
+            let job = {
+                let mut state = self.lock();
+                loop {
+                    if state.shutdown {
+                        return;
+                    }
+                    if let Some(job) = state.jobs.pop() {
+                        break job;
+                    }
+                    state = self.queued.wait(state).unwrap_or_else(PoisonError::into_inner);
+                }
+            };
+            let result = panic::catch_unwind(AssertUnwindSafe(|| unsafe { (*job.system).run(&*job.world) }));
+            let mut state = self.lock();
+            if let Err(payload) = result {
+                state.panic.get_or_insert(payload);
+            }
+            state.running -= 1;
+            if state.running == 0 {
+                self.finished.notify_all();
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct WorkerPool {
+    shared: Arc<PoolShared>,
+    threads: OnceLock<Vec<JoinHandle<()>>>,
+}
+
+impl WorkerPool {
+    fn spawn(&self) {
+        self.threads.get_or_init(|| {
+            let count =
+                thread::available_parallelism().map_or(1, |count| count.get().saturating_sub(1).max(1));
+            (0..count)
+                .map(|_| {
+                    let shared = Arc::clone(&self.shared);
+                    thread::spawn(move || shared.work())
+                })
+                .collect()
+        });
+    }
+
+    fn run<'s>(
+        &self,
+        world: &World,
+        first: &mut Box<dyn System>,
+        rest: impl Iterator<Item = &'s mut Box<dyn System>>,
+    ) {
+        self.spawn();
+        {
+            let mut state = self.shared.lock();
+            for system in rest {
+                state.jobs.push(Job { system: &mut **system, world });
+                state.running += 1;
+            }
+        }
+        self.shared.queued.notify_all();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| first.run(world)));
+        let mut state = self.shared.lock();
+        while state.running > 0 {
+            state = self.shared.finished.wait(state).unwrap_or_else(PoisonError::into_inner);
+        }
+        if let Some(payload) = result.err().or_else(|| state.panic.take()) {
+            drop(state);
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        self.shared.lock().shutdown = true;
+        self.shared.queued.notify_all();
+        for handle in self.threads.take().into_iter().flatten() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,760 @@
+use std::{
+    any::{Any, TypeId, type_name},
+    collections::HashMap,
+    hash::{BuildHasherDefault, Hasher},
+    mem,
+    sync::{
+        PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use crate::{
+    Component, ComponentHook, Entity, StorageType,
+    arguments::{Mut, QueryError, QueryFetch, QueryFilter},
+    bundle::ComponentInfo,
+};
+
+pub type TypeIdMap<V> = HashMap<TypeId, V, BuildHasherDefault<TypeIdHasher>>;
+
+#[derive(Default)]
+pub struct TypeIdHasher(u64);
+
+impl Hasher for TypeIdHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(*byte);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 ^= value;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ticks {
+    last_run: u64,
+    this_run: u64,
+}
+
+impl Ticks {
+    pub fn last_run(&self) -> u64 {
+        self.last_run
+    }
+
+    pub fn this_run(&self) -> u64 {
+        self.this_run
+    }
+
+    pub fn is_newer(&self, tick: u64) -> bool {
+        tick > self.last_run
+    }
+}
+
+pub(crate) struct Slot<T> {
+    inner: RwLock<T>,
+    added: AtomicU64,
+    changed: AtomicU64,
+}
+
+impl<T> Slot<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self::with_tick(value, 0)
+    }
+
+    pub(crate) fn with_tick(value: T, tick: u64) -> Self {
+        Self {
+            inner: RwLock::new(value),
+            added: AtomicU64::new(tick),
+            changed: AtomicU64::new(tick),
+        }
+    }
+
+    pub(crate) fn added(&self) -> u64 {
+        self.added.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn changed(&self) -> u64 {
+        self.changed.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn borrow(&self) -> RwLockReadGuard<'_, T> {
+        match self.inner.try_read() {
+            | Ok(guard) => guard,
+            | Err(TryLockError::Poisoned(error)) => error.into_inner(),
+            | Err(TryLockError::WouldBlock) => panic!("{} is already mutably borrowed", type_name::<T>()),
+        }
+    }
+
+    pub(crate) fn borrow_mut(&self) -> RwLockWriteGuard<'_, T> {
+        match self.inner.try_write() {
+            | Ok(guard) => guard,
+            | Err(TryLockError::Poisoned(error)) => error.into_inner(),
+            | Err(TryLockError::WouldBlock) => panic!("{} is already borrowed", type_name::<T>()),
+        }
+    }
+
+    pub(crate) fn borrow_tracked(&self, tick: u64) -> Mut<'_, T> {
+        Mut { inner: self.borrow_mut(), changed: &self.changed, tick }
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn replace(&mut self, value: T, tick: u64) -> T {
+        *self.changed.get_mut() = tick;
+        mem::replace(self.get_mut(), value)
+    }
+
+    pub(crate) fn into_inner(self) -> T {
+        self.inner.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct RemovalHooks {
+    pub(crate) on_replace: ComponentHook,
+    pub(crate) on_remove: ComponentHook,
+}
+
+impl RemovalHooks {
+    fn of<C>() -> Self
+    where
+        C: Component,
+    {
+        Self { on_replace: C::on_replace, on_remove: C::on_remove }
+    }
+}
+
+pub(crate) trait Column: Any + Send + Sync {
+    fn type_name(&self) -> &'static str;
+
+    fn hooks(&self) -> RemovalHooks;
+
+    fn empty(&self) -> Box<dyn Column>;
+
+    fn swap_remove(&mut self, row: usize);
+
+    fn swap_remove_into(&mut self, row: usize, other: &mut dyn Column);
+}
+
+impl dyn Column {
+    fn as_any(&self) -> &dyn Any {
+        self as &dyn Any
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self as &mut dyn Any
+    }
+}
+
+pub(crate) struct TypedColumn<T> {
+    cells: Vec<Slot<T>>,
+}
+
+impl<T> Default for TypedColumn<T> {
+    fn default() -> Self {
+        Self { cells: Vec::new() }
+    }
+}
+
+impl<T> Column for TypedColumn<T>
+where
+    T: Component,
+{
+    fn type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+
+    fn hooks(&self) -> RemovalHooks {
+        RemovalHooks::of::<T>()
+    }
+
+    fn empty(&self) -> Box<dyn Column> {
+        Box::new(Self::default())
+    }
+
+    fn swap_remove(&mut self, row: usize) {
+        self.cells.swap_remove(row);
+    }
+
+    fn swap_remove_into(&mut self, row: usize, other: &mut dyn Column) {
+        let other = other.as_any_mut().downcast_mut::<Self>().unwrap();
+        other.cells.push(self.cells.swap_remove(row));
+    }
+}
+
+pub(crate) trait SparseColumn: Any + Send + Sync {
+    fn type_name(&self) -> &'static str;
+
+    fn contains(&self, entity: Entity) -> bool;
+
+    fn hooks(&self) -> RemovalHooks;
+
+    fn remove_entity(&mut self, entity: Entity);
+}
+
+impl dyn SparseColumn {
+    fn as_any(&self) -> &dyn Any {
+        self as &dyn Any
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self as &mut dyn Any
+    }
+}
+
+pub(crate) struct SparseSet<T> {
+    sparse: Vec<Option<usize>>,
+    dense: Vec<Slot<T>>,
+    entities: Vec<Entity>,
+}
+
+impl<T> Default for SparseSet<T> {
+    fn default() -> Self {
+        Self { sparse: Vec::new(), dense: Vec::new(), entities: Vec::new() }
+    }
+}
+
+impl<T> SparseSet<T> {
+    fn index(&self, entity: Entity) -> Option<usize> {
+        let idx = (*self.sparse.get(entity.id)?)?;
+        (self.entities[idx] == entity).then_some(idx)
+    }
+
+    pub(crate) fn get(&self, entity: Entity) -> Option<&Slot<T>> {
+        Some(&self.dense[self.index(entity)?])
+    }
+
+    fn insert(&mut self, entity: Entity, value: T, tick: u64) -> Option<T> {
+        if let Some(idx) = self.index(entity) {
+            return Some(self.dense[idx].replace(value, tick));
+        }
+        if self.sparse.len() <= entity.id {
+            self.sparse.resize(entity.id + 1, None);
+        }
+        self.sparse[entity.id] = Some(self.dense.len());
+        self.dense.push(Slot::with_tick(value, tick));
+        self.entities.push(entity);
+        None
+    }
+
+    fn remove(&mut self, entity: Entity) -> Option<T> {
+        let idx = self.index(entity)?;
+        self.sparse[entity.id] = None;
+        self.entities.swap_remove(idx);
+        if let Some(swapped) = self.entities.get(idx) {
+            self.sparse[swapped.id] = Some(idx);
+        }
+        Some(self.dense.swap_remove(idx).into_inner())
+    }
+}
+
+impl<T> SparseColumn for SparseSet<T>
+where
+    T: Component,
+{
+    fn type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+
+    fn contains(&self, entity: Entity) -> bool {
+        self.index(entity).is_some()
+    }
+
+    fn hooks(&self) -> RemovalHooks {
+        RemovalHooks::of::<T>()
+    }
+
+    fn remove_entity(&mut self, entity: Entity) {
+        self.remove(entity);
+    }
+}
+
+pub struct ComponentColumn<'d, T> {
+    entities: &'d [Entity],
+    table: Option<&'d [Slot<T>]>,
+    sparse: Option<&'d SparseSet<T>>,
+}
+
+impl<'d, T> ComponentColumn<'d, T>
+where
+    T: Component,
+{
+    pub(crate) fn new(components: &'d ComponentMap, archetype: &'d Archetype) -> Self {
+        match T::STORAGE {
+            | StorageType::Table => ComponentColumn {
+                entities: archetype.entities(),
+                table: archetype.column(),
+                sparse: None,
+            },
+            | StorageType::SparseSet => ComponentColumn {
+                entities: archetype.entities(),
+                table: None,
+                sparse: components.sparse_set(),
+            },
+        }
+    }
+
+    pub(crate) fn get(&self, row: usize) -> Option<&'d Slot<T>> {
+        match T::STORAGE {
+            | StorageType::Table => Some(&self.table?[row]),
+            | StorageType::SparseSet => self.sparse?.get(self.entities[row]),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Archetype {
+    types: Vec<TypeId>,
+    columns: Vec<Box<dyn Column>>,
+    entities: Vec<Entity>,
+    add_edges: TypeIdMap<usize>,
+    remove_edges: TypeIdMap<usize>,
+}
+
+impl Archetype {
+    pub fn contains(&self, id: TypeId) -> bool {
+        self.types.binary_search(&id).is_ok()
+    }
+
+    pub fn types(&self) -> &[TypeId] {
+        &self.types
+    }
+
+    pub fn type_names(&self) -> impl Iterator<Item = &'static str> {
+        self.columns.iter().map(|column| column.type_name())
+    }
+
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    fn column_index(&self, id: TypeId) -> Option<usize> {
+        self.types.binary_search(&id).ok()
+    }
+
+    pub(crate) fn column<T>(&self) -> Option<&[Slot<T>]>
+    where
+        T: Component,
+    {
+        let column = &self.columns[self.column_index(TypeId::of::<T>())?];
+        Some(&column.as_any().downcast_ref::<TypedColumn<T>>().unwrap().cells)
+    }
+
+    fn column_mut<T>(&mut self) -> Option<&mut TypedColumn<T>>
+    where
+        T: Component,
+    {
+        let idx = self.column_index(TypeId::of::<T>())?;
+        self.columns[idx].as_any_mut().downcast_mut()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Location {
+    pub(crate) archetype: usize,
+    pub(crate) row: usize,
+}
+
+pub struct ComponentMap {
+    archetypes: Vec<Archetype>,
+    archetype_ids: HashMap<Vec<TypeId>, usize>,
+    locations: Vec<Option<Location>>,
+    sparse_sets: TypeIdMap<Box<dyn SparseColumn>>,
+    removed: TypeIdMap<Vec<(Entity, u64)>>,
+    change_tick: AtomicU64,
+}
+
+impl Default for ComponentMap {
+    fn default() -> Self {
+        Self {
+            archetypes: vec![Archetype::default()],
+            archetype_ids: HashMap::from([(Vec::new(), 0)]),
+            locations: Vec::new(),
+            sparse_sets: TypeIdMap::default(),
+            removed: TypeIdMap::default(),
+            change_tick: AtomicU64::new(1),
+        }
+    }
+}
+
+impl ComponentMap {
+    pub fn archetypes(&self) -> &[Archetype] {
+        &self.archetypes
+    }
+
+    pub(crate) fn change_tick(&self) -> u64 {
+        self.change_tick.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn current_ticks(&self) -> Ticks {
+        Ticks { last_run: 0, this_run: self.change_tick() }
+    }
+
+    pub(crate) fn advance_ticks(&self, last_run: &mut u64) -> Ticks {
+        let this_run = self.change_tick.fetch_add(1, Ordering::Relaxed);
+        Ticks { last_run: mem::replace(last_run, this_run), this_run }
+    }
+
+    pub(crate) fn location(&self, entity: Entity) -> Option<Location> {
+        let location = (*self.locations.get(entity.id)?)?;
+        (self.archetypes[location.archetype].entities[location.row] == entity).then_some(location)
+    }
+
+    pub(crate) fn locate(&self, entity: Entity) -> Option<(&Archetype, usize)> {
+        let location = self.location(entity)?;
+        Some((&self.archetypes[location.archetype], location.row))
+    }
+
+    pub(crate) fn check_entity<Q, F>(
+        &self,
+        entity: Entity,
+        ticks: Ticks,
+    ) -> Result<(&Archetype, usize), QueryError>
+    where
+        Q: QueryFetch,
+        F: QueryFilter,
+    {
+        let (archetype, row) = self.locate(entity).ok_or(QueryError::NoSuchEntity(entity))?;
+        if let Some(component) = Q::missing(self, archetype, row) {
+            return Err(QueryError::MissingComponent { entity, component });
+        }
+        if !(F::matches_archetype(archetype) && F::matches(&F::prepare(self, archetype), row, ticks)) {
+            return Err(QueryError::Filtered(entity));
+        }
+        Ok((archetype, row))
+    }
+
+    pub(crate) fn sparse_set<C>(&self) -> Option<&SparseSet<C>>
+    where
+        C: Component,
+    {
+        self.sparse_sets.get(&TypeId::of::<C>())?.as_any().downcast_ref()
+    }
+
+    fn sparse_set_mut<C>(&mut self) -> &mut SparseSet<C>
+    where
+        C: Component,
+    {
+        let set =
+            self.sparse_sets.entry(TypeId::of::<C>()).or_insert_with(|| Box::new(SparseSet::<C>::default()));
+        set.as_any_mut().downcast_mut().unwrap()
+    }
+
+    pub(crate) fn has_sparse<C>(&self, entity: Entity) -> bool
+    where
+        C: Component,
+    {
+        self.sparse_set::<C>().is_some_and(|set| set.contains(entity))
+    }
+
+    pub(crate) fn contains<C>(&self, entity: Entity) -> bool
+    where
+        C: Component,
+    {
+        match C::STORAGE {
+            | StorageType::Table => {
+                self.locate(entity).is_some_and(|(archetype, _)| archetype.contains(TypeId::of::<C>()))
+            }
+            | StorageType::SparseSet => self.has_sparse::<C>(entity),
+        }
+    }
+
+    pub(crate) fn slot<C>(&self, entity: Entity) -> Option<&Slot<C>>
+    where
+        C: Component,
+    {
+        match C::STORAGE {
+            | StorageType::Table => {
+                let (archetype, row) = self.locate(entity)?;
+                Some(&archetype.column::<C>()?[row])
+            }
+            | StorageType::SparseSet => self.sparse_set::<C>()?.get(entity),
+        }
+    }
+
+    pub(crate) fn component_types(&self, entity: Entity) -> Vec<(TypeId, &'static str)> {
+        let Some((archetype, _)) = self.locate(entity) else {
+            return Vec::new();
+        };
+        let sparse = self
+            .sparse_sets
+            .iter()
+            .filter(|(_, set)| set.contains(entity))
+            .map(|(id, set)| (*id, set.type_name()));
+        archetype.types.iter().copied().zip(archetype.type_names()).chain(sparse).collect()
+    }
+
+    pub(crate) fn removal_hooks(&self, entity: Entity) -> Vec<RemovalHooks> {
+        let Some((archetype, _)) = self.locate(entity) else {
+            return Vec::new();
+        };
+        let sparse = self.sparse_sets.values().filter(|set| set.contains(entity)).map(|set| set.hooks());
+        archetype.columns.iter().map(|column| column.hooks()).chain(sparse).collect()
+    }
+
+    fn set_location(&mut self, entity: Entity, location: Option<Location>) {
+        if self.locations.len() <= entity.id {
+            self.locations.resize(entity.id + 1, None);
+        }
+        self.locations[entity.id] = location;
+    }
+
+    pub(crate) fn spawn(&mut self, entity: Entity) {
+        if self.location(entity).is_some() {
+            return;
+        }
+        let empty = &mut self.archetypes[0];
+        empty.entities.push(entity);
+        let row = empty.entities.len() - 1;
+        self.set_location(entity, Some(Location { archetype: 0, row }));
+    }
+
+    pub(crate) fn despawn(&mut self, entity: Entity) {
+        let Some(location) = self.location(entity) else {
+            return;
+        };
+        let tick = self.change_tick();
+        for (id, set) in self.sparse_sets.iter_mut() {
+            if set.contains(entity) {
+                set.remove_entity(entity);
+                self.removed.entry(*id).or_default().push((entity, tick));
+            }
+        }
+        let archetype = &mut self.archetypes[location.archetype];
+        for (id, column) in archetype.types.iter().zip(archetype.columns.iter_mut()) {
+            column.swap_remove(location.row);
+            self.removed.entry(*id).or_default().push((entity, tick));
+        }
+        archetype.entities.swap_remove(location.row);
+        if let Some(&swapped) = archetype.entities.get(location.row) {
+            self.set_location(swapped, Some(location));
+        }
+        self.set_location(entity, None);
+    }
+
+    pub(crate) fn insert<C>(&mut self, entity: Entity, component: C) -> Option<C>
+    where
+        C: Component,
+    {
+        self.spawn(entity);
+        let location = self.location(entity).unwrap();
+        let id = TypeId::of::<C>();
+        if C::STORAGE == StorageType::Table && !self.archetypes[location.archetype].contains(id) {
+            let dst = self.add_target(location.archetype, id, || Box::new(TypedColumn::<C>::default()));
+            self.move_entity(entity, location, dst, |_, _| unreachable!());
+        }
+        self.write(entity, component)
+    }
+
+    pub(crate) fn write<C>(&mut self, entity: Entity, component: C) -> Option<C>
+    where
+        C: Component,
+    {
+        let tick = self.change_tick();
+        if C::STORAGE == StorageType::SparseSet {
+            return self.sparse_set_mut().insert(entity, component, tick);
+        }
+        let location = self.location(entity).unwrap();
+        let column = self.archetypes[location.archetype].column_mut::<C>().unwrap();
+        match column.cells.get_mut(location.row) {
+            | Some(slot) => Some(slot.replace(component, tick)),
+            | None => {
+                column.cells.push(Slot::with_tick(component, tick));
+                None
+            }
+        }
+    }
+
+    pub(crate) fn insert_bundle(&mut self, entity: Entity, infos: &[ComponentInfo]) {
+        self.spawn(entity);
+        let location = self.location(entity).unwrap();
+        let src = location.archetype;
+        let mut types = self.archetypes[src].types.clone();
+        for info in infos.iter().filter(|info| info.storage == StorageType::Table) {
+            if let Err(idx) = types.binary_search(&info.id) {
+                types.insert(idx, info.id);
+            }
+        }
+        if types.len() == self.archetypes[src].types.len() {
+            return;
+        }
+        let dst = self.archetype_with(types.clone(), |archetypes| {
+            types
+                .iter()
+                .map(|id| match archetypes[src].column_index(*id) {
+                    | Some(idx) => archetypes[src].columns[idx].empty(),
+                    | None => (infos.iter().find(|info| info.id == *id).unwrap().column)(),
+                })
+                .collect()
+        });
+        self.move_entity(entity, location, dst, |_, _| unreachable!());
+    }
+
+    pub(crate) fn remove_bundle(&mut self, entity: Entity, infos: &[ComponentInfo]) {
+        let Some(location) = self.location(entity) else {
+            return;
+        };
+        let tick = self.change_tick();
+        for info in infos.iter().filter(|info| info.storage == StorageType::SparseSet) {
+            if let Some(set) = self.sparse_sets.get_mut(&info.id).filter(|set| set.contains(entity)) {
+                set.remove_entity(entity);
+                self.removed.entry(info.id).or_default().push((entity, tick));
+            }
+        }
+        let src = location.archetype;
+        let bundled = |id: &TypeId| infos.iter().any(|info| info.id == *id);
+        let (removed, types): (Vec<TypeId>, Vec<TypeId>) =
+            self.archetypes[src].types.iter().partition(|id| bundled(id));
+        if removed.is_empty() {
+            return;
+        }
+        let dst = self.archetype_with(types, |archetypes| {
+            let source = &archetypes[src];
+            source
+                .types
+                .iter()
+                .zip(source.columns.iter())
+                .filter(|(id, _)| !bundled(id))
+                .map(|(_, column)| column.empty())
+                .collect()
+        });
+        self.move_entity(entity, location, dst, |column, row| column.swap_remove(row));
+        for id in removed {
+            self.removed.entry(id).or_default().push((entity, tick));
+        }
+    }
+
+    pub(crate) fn remove<C>(&mut self, entity: Entity) -> Option<C>
+    where
+        C: Component,
+    {
+        let removed = self.detach::<C>(entity)?;
+        let tick = self.change_tick();
+        self.removed.entry(TypeId::of::<C>()).or_default().push((entity, tick));
+        Some(removed)
+    }
+
+    pub(crate) fn removed<C>(&self) -> &[(Entity, u64)]
+    where
+        C: Component,
+    {
+        self.removed.get(&TypeId::of::<C>()).map_or(&[], Vec::as_slice)
+    }
+
+    pub(crate) fn trim_removed(&mut self, before: u64) {
+        for log in self.removed.values_mut() {
+            log.retain(|(_, tick)| *tick >= before);
+        }
+    }
+
+    fn detach<C>(&mut self, entity: Entity) -> Option<C>
+    where
+        C: Component,
+    {
+        let location = self.location(entity)?;
+        if C::STORAGE == StorageType::SparseSet {
+            let set = self.sparse_sets.get_mut(&TypeId::of::<C>())?;
+            return set.as_any_mut().downcast_mut::<SparseSet<C>>().unwrap().remove(entity);
+        }
+        let id = TypeId::of::<C>();
+        if !self.archetypes[location.archetype].contains(id) {
+            return None;
+        }
+
+        let dst = self.remove_target(location.archetype, id);
+        let mut removed = None;
+        self.move_entity(entity, location, dst, |column, row| {
+            let column = column.as_any_mut().downcast_mut::<TypedColumn<C>>().unwrap();
+            removed = Some(column.cells.swap_remove(row).into_inner());
+        });
+        removed
+    }
+
+    fn move_entity(
+        &mut self,
+        entity: Entity,
+        location: Location,
+        dst: usize,
+        mut leftover: impl FnMut(&mut dyn Column, usize),
+    ) -> Location {
+        let [src, target] = self.archetypes.get_disjoint_mut([location.archetype, dst]).unwrap();
+        for (id, column) in src.types.iter().zip(src.columns.iter_mut()) {
+            match target.column_index(*id) {
+                | Some(idx) => column.swap_remove_into(location.row, &mut *target.columns[idx]),
+                | None => leftover(&mut **column, location.row),
+            }
+        }
+        src.entities.swap_remove(location.row);
+        let swapped = src.entities.get(location.row).copied();
+        target.entities.push(entity);
+        let moved = Location { archetype: dst, row: target.entities.len() - 1 };
+
+        if let Some(swapped) = swapped {
+            self.set_location(swapped, Some(location));
+        }
+        self.set_location(entity, Some(moved));
+        moved
+    }
+
+    fn add_target(&mut self, src: usize, id: TypeId, column: impl FnOnce() -> Box<dyn Column>) -> usize {
+        if let Some(&dst) = self.archetypes[src].add_edges.get(&id) {
+            return dst;
+        }
+        let mut types = self.archetypes[src].types.clone();
+        let idx = types.binary_search(&id).unwrap_err();
+        types.insert(idx, id);
+        let dst = self.archetype_with(types, |archetypes| {
+            let mut columns: Vec<_> = archetypes[src].columns.iter().map(|column| column.empty()).collect();
+            columns.insert(idx, column());
+            columns
+        });
+        self.archetypes[src].add_edges.insert(id, dst);
+        dst
+    }
+
+    fn remove_target(&mut self, src: usize, id: TypeId) -> usize {
+        if let Some(&dst) = self.archetypes[src].remove_edges.get(&id) {
+            return dst;
+        }
+        let mut types = self.archetypes[src].types.clone();
+        let idx = types.binary_search(&id).unwrap();
+        types.remove(idx);
+        let dst = self.archetype_with(types, |archetypes| {
+            let mut columns: Vec<_> = archetypes[src].columns.iter().map(|column| column.empty()).collect();
+            columns.remove(idx);
+            columns
+        });
+        self.archetypes[src].remove_edges.insert(id, dst);
+        dst
+    }
+
+    fn archetype_with(
+        &mut self,
+        types: Vec<TypeId>,
+        columns: impl FnOnce(&[Archetype]) -> Vec<Box<dyn Column>>,
+    ) -> usize {
+        if let Some(&id) = self.archetype_ids.get(&types) {
+            return id;
+        }
+        let columns = columns(&self.archetypes);
+        self.archetypes.push(Archetype { types: types.clone(), columns, ..Default::default() });
+        self.archetype_ids.insert(types, self.archetypes.len() - 1);
+        self.archetypes.len() - 1
+    }
+}
@@ -1,30 +1,237 @@
-use std::marker::PhantomData;
+use std::{
+    any::{TypeId, type_name},
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+    marker::PhantomData,
+};
 
-use crate::{ComponentMap, System};
+use crate::{
+    System, World,
+    schedule::{Ordered, RunIf, Stage, SystemLabel},
+    storage::Ticks,
+};
 
 pub trait SystemBuilder<In> {
     type System;
 
     fn build_system(self) -> Self::System;
+
+    fn before<B, M>(self, other: B) -> Ordered<Self::System>
+    where
+        Self: Sized,
+        Self::System: System,
+        B: SystemBuilder<M>,
+        B::System: System,
+    {
+        Ordered::new(self.build_system()).run_before(other.build_system().label())
+    }
+
+    fn after<B, M>(self, other: B) -> Ordered<Self::System>
+    where
+        Self: Sized,
+        Self::System: System,
+        B: SystemBuilder<M>,
+        B::System: System,
+    {
+        Ordered::new(self.build_system()).run_after(other.build_system().label())
+    }
+
+    fn run_if<C, M>(self, condition: C) -> RunIf<Self::System, C::System>
+    where
+        Self: Sized,
+        Self::System: System,
+        C: SystemBuilder<M>,
+        C::System: Condition,
+    {
+        RunIf::new(self.build_system(), condition.build_system())
+    }
+}
+
+pub trait Condition
+where
+    Self: Send + Sync,
+{
+    fn evaluate(&mut self, world: &World) -> bool;
+
+    fn name(&self) -> &'static str;
+
+    fn access(&self) -> Access;
 }
 
-pub struct FnSystem<In, Func> {
+pub struct FnSystem<In, Func, State> {
     func: Func,
+    state: State,
+    last_run: u64,
     marker: PhantomData<fn() -> In>,
 }
 
-pub(crate) trait SystemArg {
+pub trait SystemArg {
     type Item<'o>;
 
-    fn fetch<'i>(components: &'i ComponentMap) -> Self::Item<'i>;
+    type State: Default + Send + Sync;
+
+    fn fetch<'i>(world: &'i World, state: &'i mut Self::State, ticks: Ticks) -> Self::Item<'i>;
+
+    fn apply(_: &mut Self::State, _: &mut World) {}
+
+    fn access(_: &mut Access) {}
+}
+
+pub trait ReadOnlySystemArg: SystemArg {}
+
+#[derive(Debug, Default, Clone)]
+struct AccessSet {
+    reads: Vec<(TypeId, &'static str)>,
+    writes: Vec<(TypeId, &'static str)>,
+    aliased: Vec<&'static str>,
+}
+
+impl AccessSet {
+    fn contains(set: &[(TypeId, &'static str)], id: TypeId) -> bool {
+        set.iter().any(|(other, _)| *other == id)
+    }
+
+    fn read<T>(&mut self)
+    where
+        T: 'static,
+    {
+        let id = TypeId::of::<T>();
+        if Self::contains(&self.writes, id) {
+            self.aliased.push(type_name::<T>());
+        }
+        self.reads.push((id, type_name::<T>()));
+    }
+
+    fn write<T>(&mut self)
+    where
+        T: 'static,
+    {
+        let id = TypeId::of::<T>();
+        if Self::contains(&self.reads, id) || Self::contains(&self.writes, id) {
+            self.aliased.push(type_name::<T>());
+        }
+        self.writes.push((id, type_name::<T>()));
+    }
+
+    fn extend(&mut self, other: &AccessSet) {
+        self.reads.extend_from_slice(&other.reads);
+        self.writes.extend_from_slice(&other.writes);
+    }
+
+    fn conflict(&self, other: &AccessSet) -> Option<&'static str> {
+        self.writes
+            .iter()
+            .find(|(id, _)| Self::contains(&other.reads, *id) || Self::contains(&other.writes, *id))
+            .or_else(|| other.writes.iter().find(|(id, _)| Self::contains(&self.reads, *id)))
+            .map(|(_, name)| *name)
+    }
 }
 
-impl<Func> System for FnSystem<((),), Func>
+#[derive(Debug, Default, Clone)]
+pub struct Access {
+    components: AccessSet,
+    resources: AccessSet,
+}
+
+impl Access {
+    pub fn read_component<T>(&mut self)
+    where
+        T: 'static,
+    {
+        self.components.read::<T>();
+    }
+
+    pub fn write_component<T>(&mut self)
+    where
+        T: 'static,
+    {
+        self.components.write::<T>();
+    }
+
+    pub fn read_resource<T>(&mut self)
+    where
+        T: 'static,
+    {
+        self.resources.read::<T>();
+    }
+
+    pub fn write_resource<T>(&mut self)
+    where
+        T: 'static,
+    {
+        self.resources.write::<T>();
+    }
+
+    pub fn extend(&mut self, other: &Access) {
+        self.components.extend(&other.components);
+        self.resources.extend(&other.resources);
+    }
+
+    pub fn conflict(&self, other: &Access) -> Option<&'static str> {
+        self.components.conflict(&other.components).or_else(|| self.resources.conflict(&other.resources))
+    }
+
+    pub fn is_compatible(&self, other: &Access) -> bool {
+        self.conflict(other).is_none()
+    }
+
+    pub fn validate(&self, system: &'static str) -> Result<(), SystemError> {
+        if let Some(component) = self.components.aliased.first() {
+            return Err(SystemError::ConflictingComponent { system, component });
+        }
+        if let Some(resource) = self.resources.aliased.first() {
+            return Err(SystemError::ConflictingResource { system, resource });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SystemId(pub(crate) usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemError {
+    ConflictingComponent { system: &'static str, component: &'static str },
+    ConflictingResource { system: &'static str, resource: &'static str },
+    UnknownSystem(SystemId),
+    UnknownStage(Stage),
+}
+
+impl Display for SystemError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            | SystemError::ConflictingComponent { system, component } => {
+                write!(
+                    f,
+                    "system {} borrows component {} mutably more than once or while reading it",
+                    system, component
+                )
+            }
+            | SystemError::ConflictingResource { system, resource } => {
+                write!(
+                    f,
+                    "system {} borrows resource {} mutably more than once or while reading it",
+                    system, resource
+                )
+            }
+            | SystemError::UnknownSystem(id) => {
+                write!(f, "system id {} is not registered", id.0)
+            }
+            | SystemError::UnknownStage(stage) => {
+                write!(f, "stage {:?} does not exist", stage)
+            }
+        }
+    }
+}
+
+impl Error for SystemError {}
+
+impl<Func> System for FnSystem<fn(), Func, ()>
 where
     for<'a> &'a mut Func: FnMut(),
-    Func: Send + Sync,
+    Func: Send + Sync + 'static,
 {
-    fn run(&mut self, _: &mut ComponentMap) {
+    fn run(&mut self, _: &World) {
         call_func(&mut self.func);
 
         fn call_func<F>(mut func: F)
@@ -34,16 +241,29 @@ where
             func()
         }
     }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn label(&self) -> SystemLabel {
+        SystemLabel::of::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        Access::default()
+    }
 }
 
-impl<Func, T0> System for FnSystem<(T0,), Func>
+impl<Func, T0> System for FnSystem<fn(T0), Func, (T0::State,)>
 where
     for<'a, 'b> &'a mut Func: FnMut(T0) + FnMut(T0::Item<'b>),
     T0: SystemArg,
-    Func: Send + Sync,
+    Func: Send + Sync + 'static,
 {
-    fn run(&mut self, map: &mut ComponentMap) {
-        let p0 = T0::fetch(map);
+    fn run(&mut self, world: &World) {
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
         call_func(&mut self.func, p0);
 
         fn call_func<T0, F>(mut func: F, p0: T0)
@@ -53,18 +273,37 @@ where
             func(p0)
         }
     }
+
+    fn apply(&mut self, world: &mut World) {
+        T0::apply(&mut self.state.0, world);
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn label(&self) -> SystemLabel {
+        SystemLabel::of::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        access
+    }
 }
 
-impl<Func, T0, T1> System for FnSystem<(T0, T1), Func>
+impl<Func, T0, T1> System for FnSystem<fn(T0, T1), Func, (T0::State, T1::State)>
 where
     for<'a, 'b> &'a mut Func: FnMut(T0, T1) + FnMut(T0::Item<'b>, T1::Item<'b>),
     T0: SystemArg,
     T1: SystemArg,
-    Func: Send + Sync,
+    Func: Send + Sync + 'static,
 {
-    fn run(&mut self, map: &mut ComponentMap) {
-        let p0 = T0::fetch(map);
-        let p1 = T1::fetch(map);
+    fn run(&mut self, world: &World) {
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
         call_func(&mut self.func, p0, p1);
 
         fn call_func<T0, T1, F>(mut func: F, p0: T0, p1: T1)
@@ -74,20 +313,41 @@ where
             func(p0, p1)
         }
     }
+
+    fn apply(&mut self, world: &mut World) {
+        T0::apply(&mut self.state.0, world);
+        T1::apply(&mut self.state.1, world);
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn label(&self) -> SystemLabel {
+        SystemLabel::of::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        access
+    }
 }
 
-impl<Func, T0, T1, T2> System for FnSystem<(T0, T1, T2), Func>
+impl<Func, T0, T1, T2> System for FnSystem<fn(T0, T1, T2), Func, (T0::State, T1::State, T2::State)>
 where
     for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2) + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>),
     T0: SystemArg,
     T1: SystemArg,
     T2: SystemArg,
-    Func: Send + Sync,
+    Func: Send + Sync + 'static,
 {
-    fn run(&mut self, map: &mut ComponentMap) {
-        let p0 = T0::fetch(map);
-        let p1 = T1::fetch(map);
-        let p2 = T2::fetch(map);
+    fn run(&mut self, world: &World) {
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
         call_func(&mut self.func, p0, p1, p2);
 
         fn call_func<T0, T1, T2, F>(mut func: F, p0: T0, p1: T1, p2: T2)
@@ -97,9 +357,32 @@ where
             func(p0, p1, p2)
         }
     }
+
+    fn apply(&mut self, world: &mut World) {
+        T0::apply(&mut self.state.0, world);
+        T1::apply(&mut self.state.1, world);
+        T2::apply(&mut self.state.2, world);
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn label(&self) -> SystemLabel {
+        SystemLabel::of::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        access
+    }
 }
 
-impl<Func, T0, T1, T2, T3> System for FnSystem<(T0, T1, T2, T3), Func>
+impl<Func, T0, T1, T2, T3> System
+    for FnSystem<fn(T0, T1, T2, T3), Func, (T0::State, T1::State, T2::State, T3::State)>
 where
     for<'a, 'b> &'a mut Func:
         FnMut(T0, T1, T2, T3) + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>, T3::Item<'b>),
@@ -107,13 +390,14 @@ where
     T1: SystemArg,
     T2: SystemArg,
     T3: SystemArg,
-    Func: Send + Sync,
+    Func: Send + Sync + 'static,
 {
-    fn run(&mut self, map: &mut ComponentMap) {
-        let p0 = T0::fetch(map);
-        let p1 = T1::fetch(map);
-        let p2 = T2::fetch(map);
-        let p3 = T3::fetch(map);
+    fn run(&mut self, world: &World) {
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
         call_func(&mut self.func, p0, p1, p2, p3);
 
         fn call_func<T0, T1, T2, T3, F>(mut func: F, p0: T0, p1: T1, p2: T2, p3: T3)
@@ -123,9 +407,34 @@ where
             func(p0, p1, p2, p3)
         }
     }
+
+    fn apply(&mut self, world: &mut World) {
+        T0::apply(&mut self.state.0, world);
+        T1::apply(&mut self.state.1, world);
+        T2::apply(&mut self.state.2, world);
+        T3::apply(&mut self.state.3, world);
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn label(&self) -> SystemLabel {
+        SystemLabel::of::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        access
+    }
 }
 
-impl<Func, T0, T1, T2, T3, T4> System for FnSystem<(T0, T1, T2, T3, T4), Func>
+impl<Func, T0, T1, T2, T3, T4> System
+    for FnSystem<fn(T0, T1, T2, T3, T4), Func, (T0::State, T1::State, T2::State, T3::State, T4::State)>
 where
     for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4)
         + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>, T3::Item<'b>, T4::Item<'b>),
@@ -134,14 +443,15 @@ where
     T2: SystemArg,
     T3: SystemArg,
     T4: SystemArg,
-    Func: Send + Sync,
-{
-    fn run(&mut self, map: &mut ComponentMap) {
-        let p0 = T0::fetch(map);
-        let p1 = T1::fetch(map);
-        let p2 = T2::fetch(map);
-        let p3 = T3::fetch(map);
-        let p4 = T4::fetch(map);
+    Func: Send + Sync + 'static,
+{
+    fn run(&mut self, world: &World) {
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        let p4 = T4::fetch(world, &mut self.state.4, ticks);
         call_func(&mut self.func, p0, p1, p2, p3, p4);
 
         fn call_func<T0, T1, T2, T3, T4, F>(mut func: F, p0: T0, p1: T1, p2: T2, p3: T3, p4: T4)
@@ -151,9 +461,40 @@ where
             func(p0, p1, p2, p3, p4)
         }
     }
+
+    fn apply(&mut self, world: &mut World) {
+        T0::apply(&mut self.state.0, world);
+        T1::apply(&mut self.state.1, world);
+        T2::apply(&mut self.state.2, world);
+        T3::apply(&mut self.state.3, world);
+        T4::apply(&mut self.state.4, world);
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn label(&self) -> SystemLabel {
+        SystemLabel::of::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        T4::access(&mut access);
+        access
+    }
 }
 
-impl<Func, T0, T1, T2, T3, T4, T5> System for FnSystem<(T0, T1, T2, T3, T4, T5), Func>
+impl<Func, T0, T1, T2, T3, T4, T5> System
+    for FnSystem<
+        fn(T0, T1, T2, T3, T4, T5),
+        Func,
+        (T0::State, T1::State, T2::State, T3::State, T4::State, T5::State),
+    >
 where
     for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5)
         + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>, T3::Item<'b>, T4::Item<'b>, T5::Item<'b>),
@@ -163,15 +504,16 @@ where
     T3: SystemArg,
     T4: SystemArg,
     T5: SystemArg,
-    Func: Send + Sync,
-{
-    fn run(&mut self, map: &mut ComponentMap) {
-        let p0 = T0::fetch(map);
-        let p1 = T1::fetch(map);
-        let p2 = T2::fetch(map);
-        let p3 = T3::fetch(map);
-        let p4 = T4::fetch(map);
-        let p5 = T5::fetch(map);
+    Func: Send + Sync + 'static,
+{
+    fn run(&mut self, world: &World) {
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        let p4 = T4::fetch(world, &mut self.state.4, ticks);
+        let p5 = T5::fetch(world, &mut self.state.5, ticks);
         call_func(&mut self.func, p0, p1, p2, p3, p4, p5);
 
         fn call_func<T0, T1, T2, T3, T4, T5, F>(mut func: F, p0: T0, p1: T1, p2: T2, p3: T3, p4: T4, p5: T5)
@@ -181,9 +523,42 @@ where
             func(p0, p1, p2, p3, p4, p5)
         }
     }
+
+    fn apply(&mut self, world: &mut World) {
+        T0::apply(&mut self.state.0, world);
+        T1::apply(&mut self.state.1, world);
+        T2::apply(&mut self.state.2, world);
+        T3::apply(&mut self.state.3, world);
+        T4::apply(&mut self.state.4, world);
+        T5::apply(&mut self.state.5, world);
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn label(&self) -> SystemLabel {
+        SystemLabel::of::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        T4::access(&mut access);
+        T5::access(&mut access);
+        access
+    }
 }
 
-impl<Func, T0, T1, T2, T3, T4, T5, T6> System for FnSystem<(T0, T1, T2, T3, T4, T5, T6), Func>
+impl<Func, T0, T1, T2, T3, T4, T5, T6> System
+    for FnSystem<
+        fn(T0, T1, T2, T3, T4, T5, T6),
+        Func,
+        (T0::State, T1::State, T2::State, T3::State, T4::State, T5::State, T6::State),
+    >
 where
     for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6)
         + FnMut(
@@ -202,17 +577,18 @@ where
     T4: SystemArg,
     T5: SystemArg,
     T6: SystemArg,
-    Func: Send + Sync,
+    Func: Send + Sync + 'static,
 {
     #[allow(clippy::too_many_arguments)]
-    fn run(&mut self, map: &mut ComponentMap) {
-        let p0 = T0::fetch(map);
-        let p1 = T1::fetch(map);
-        let p2 = T2::fetch(map);
-        let p3 = T3::fetch(map);
-        let p4 = T4::fetch(map);
-        let p5 = T5::fetch(map);
-        let p6 = T6::fetch(map);
+    fn run(&mut self, world: &World) {
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        let p4 = T4::fetch(world, &mut self.state.4, ticks);
+        let p5 = T5::fetch(world, &mut self.state.5, ticks);
+        let p6 = T6::fetch(world, &mut self.state.6, ticks);
         call_func(&mut self.func, p0, p1, p2, p3, p4, p5, p6);
 
         fn call_func<T0, T1, T2, T3, T4, T5, T6, F>(
@@ -230,9 +606,44 @@ where
             func(p0, p1, p2, p3, p4, p5, p6)
         }
     }
+
+    fn apply(&mut self, world: &mut World) {
+        T0::apply(&mut self.state.0, world);
+        T1::apply(&mut self.state.1, world);
+        T2::apply(&mut self.state.2, world);
+        T3::apply(&mut self.state.3, world);
+        T4::apply(&mut self.state.4, world);
+        T5::apply(&mut self.state.5, world);
+        T6::apply(&mut self.state.6, world);
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn label(&self) -> SystemLabel {
+        SystemLabel::of::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        T4::access(&mut access);
+        T5::access(&mut access);
+        T6::access(&mut access);
+        access
+    }
 }
 
-impl<Func, T0, T1, T2, T3, T4, T5, T6, T7> System for FnSystem<(T0, T1, T2, T3, T4, T5, T6, T7), Func>
+impl<Func, T0, T1, T2, T3, T4, T5, T6, T7> System
+    for FnSystem<
+        fn(T0, T1, T2, T3, T4, T5, T6, T7),
+        Func,
+        (T0::State, T1::State, T2::State, T3::State, T4::State, T5::State, T6::State, T7::State),
+    >
 where
     for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7)
         + FnMut(
@@ -253,18 +664,19 @@ where
     T5: SystemArg,
     T6: SystemArg,
     T7: SystemArg,
-    Func: Send + Sync,
+    Func: Send + Sync + 'static,
 {
     #[allow(clippy::too_many_arguments)]
-    fn run(&mut self, map: &mut ComponentMap) {
-        let p0 = T0::fetch(map);
-        let p1 = T1::fetch(map);
-        let p2 = T2::fetch(map);
-        let p3 = T3::fetch(map);
-        let p4 = T4::fetch(map);
-        let p5 = T5::fetch(map);
-        let p6 = T6::fetch(map);
-        let p7 = T7::fetch(map);
+    fn run(&mut self, world: &World) {
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        let p4 = T4::fetch(world, &mut self.state.4, ticks);
+        let p5 = T5::fetch(world, &mut self.state.5, ticks);
+        let p6 = T6::fetch(world, &mut self.state.6, ticks);
+        let p7 = T7::fetch(world, &mut self.state.7, ticks);
         call_func(&mut self.func, p0, p1, p2, p3, p4, p5, p6, p7);
 
         fn call_func<T0, T1, T2, T3, T4, T5, T6, T7, F>(
@@ -283,9 +695,46 @@ where
             func(p0, p1, p2, p3, p4, p5, p6, p7)
         }
     }
+
+    fn apply(&mut self, world: &mut World) {
+        T0::apply(&mut self.state.0, world);
+        T1::apply(&mut self.state.1, world);
+        T2::apply(&mut self.state.2, world);
+        T3::apply(&mut self.state.3, world);
+        T4::apply(&mut self.state.4, world);
+        T5::apply(&mut self.state.5, world);
+        T6::apply(&mut self.state.6, world);
+        T7::apply(&mut self.state.7, world);
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn label(&self) -> SystemLabel {
+        SystemLabel::of::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        T4::access(&mut access);
+        T5::access(&mut access);
+        T6::access(&mut access);
+        T7::access(&mut access);
+        access
+    }
 }
 
-impl<Func, T0, T1, T2, T3, T4, T5, T6, T7, T8> System for FnSystem<(T0, T1, T2, T3, T4, T5, T6, T7, T8), Func>
+impl<Func, T0, T1, T2, T3, T4, T5, T6, T7, T8> System
+    for FnSystem<
+        fn(T0, T1, T2, T3, T4, T5, T6, T7, T8),
+        Func,
+        (T0::State, T1::State, T2::State, T3::State, T4::State, T5::State, T6::State, T7::State, T8::State),
+    >
 where
     for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8)
         + FnMut(
@@ -308,19 +757,20 @@ where
     T6: SystemArg,
     T7: SystemArg,
     T8: SystemArg,
-    Func: Send + Sync,
+    Func: Send + Sync + 'static,
 {
     #[allow(clippy::too_many_arguments)]
-    fn run(&mut self, map: &mut ComponentMap) {
-        let p0 = T0::fetch(map);
-        let p1 = T1::fetch(map);
-        let p2 = T2::fetch(map);
-        let p3 = T3::fetch(map);
-        let p4 = T4::fetch(map);
-        let p5 = T5::fetch(map);
-        let p6 = T6::fetch(map);
-        let p7 = T7::fetch(map);
-        let p8 = T8::fetch(map);
+    fn run(&mut self, world: &World) {
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        let p4 = T4::fetch(world, &mut self.state.4, ticks);
+        let p5 = T5::fetch(world, &mut self.state.5, ticks);
+        let p6 = T6::fetch(world, &mut self.state.6, ticks);
+        let p7 = T7::fetch(world, &mut self.state.7, ticks);
+        let p8 = T8::fetch(world, &mut self.state.8, ticks);
         call_func(&mut self.func, p0, p1, p2, p3, p4, p5, p6, p7, p8);
 
         fn call_func<T0, T1, T2, T3, T4, T5, T6, T7, T8, F>(
@@ -340,10 +790,59 @@ where
             func(p0, p1, p2, p3, p4, p5, p6, p7, p8)
         }
     }
+
+    fn apply(&mut self, world: &mut World) {
+        T0::apply(&mut self.state.0, world);
+        T1::apply(&mut self.state.1, world);
+        T2::apply(&mut self.state.2, world);
+        T3::apply(&mut self.state.3, world);
+        T4::apply(&mut self.state.4, world);
+        T5::apply(&mut self.state.5, world);
+        T6::apply(&mut self.state.6, world);
+        T7::apply(&mut self.state.7, world);
+        T8::apply(&mut self.state.8, world);
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn label(&self) -> SystemLabel {
+        SystemLabel::of::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        T4::access(&mut access);
+        T5::access(&mut access);
+        T6::access(&mut access);
+        T7::access(&mut access);
+        T8::access(&mut access);
+        access
+    }
 }
 
 impl<Func, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9> System
-    for FnSystem<(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9), Func>
+    for FnSystem<
+        fn(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9),
+        Func,
+        (
+            T0::State,
+            T1::State,
+            T2::State,
+            T3::State,
+            T4::State,
+            T5::State,
+            T6::State,
+            T7::State,
+            T8::State,
+            T9::State,
+        ),
+    >
 where
     for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9)
         + FnMut(
@@ -368,20 +867,21 @@ where
     T7: SystemArg,
     T8: SystemArg,
     T9: SystemArg,
-    Func: Send + Sync,
+    Func: Send + Sync + 'static,
 {
     #[allow(clippy::too_many_arguments)]
-    fn run(&mut self, map: &mut ComponentMap) {
-        let p0 = T0::fetch(map);
-        let p1 = T1::fetch(map);
-        let p2 = T2::fetch(map);
-        let p3 = T3::fetch(map);
-        let p4 = T4::fetch(map);
-        let p5 = T5::fetch(map);
-        let p6 = T6::fetch(map);
-        let p7 = T7::fetch(map);
-        let p8 = T8::fetch(map);
-        let p9 = T9::fetch(map);
+    fn run(&mut self, world: &World) {
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        let p4 = T4::fetch(world, &mut self.state.4, ticks);
+        let p5 = T5::fetch(world, &mut self.state.5, ticks);
+        let p6 = T6::fetch(world, &mut self.state.6, ticks);
+        let p7 = T7::fetch(world, &mut self.state.7, ticks);
+        let p8 = T8::fetch(world, &mut self.state.8, ticks);
+        let p9 = T9::fetch(world, &mut self.state.9, ticks);
         call_func(&mut self.func, p0, p1, p2, p3, p4, p5, p6, p7, p8, p9);
 
         fn call_func<T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, F>(
@@ -402,102 +902,770 @@ where
             func(p0, p1, p2, p3, p4, p5, p6, p7, p8, p9)
         }
     }
+
+    fn apply(&mut self, world: &mut World) {
+        T0::apply(&mut self.state.0, world);
+        T1::apply(&mut self.state.1, world);
+        T2::apply(&mut self.state.2, world);
+        T3::apply(&mut self.state.3, world);
+        T4::apply(&mut self.state.4, world);
+        T5::apply(&mut self.state.5, world);
+        T6::apply(&mut self.state.6, world);
+        T7::apply(&mut self.state.7, world);
+        T8::apply(&mut self.state.8, world);
+        T9::apply(&mut self.state.9, world);
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn label(&self) -> SystemLabel {
+        SystemLabel::of::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        T4::access(&mut access);
+        T5::access(&mut access);
+        T6::access(&mut access);
+        T7::access(&mut access);
+        T8::access(&mut access);
+        T9::access(&mut access);
+        access
+    }
 }
 
-impl<Func> SystemBuilder<()> for Func
+impl<Func> Condition for FnSystem<fn() -> bool, Func, ()>
 where
-    for<'a> &'a mut Func: FnMut(),
-    Func: FnMut() + Send + Sync,
+    for<'a> &'a mut Func: FnMut() -> bool,
+    Func: Send + Sync + 'static,
+{
+    fn evaluate(&mut self, _: &World) -> bool {
+        fn call_func<F>(mut func: F) -> bool
+        where
+            F: FnMut() -> bool,
+        {
+            func()
+        }
+
+        call_func(&mut self.func)
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        Access::default()
+    }
+}
+
+impl<Func, T0> Condition for FnSystem<fn(T0) -> bool, Func, (T0::State,)>
+where
+    for<'a, 'b> &'a mut Func: FnMut(T0) -> bool + FnMut(T0::Item<'b>) -> bool,
+    T0: ReadOnlySystemArg,
+    Func: Send + Sync + 'static,
+{
+    fn evaluate(&mut self, world: &World) -> bool {
+        fn call_func<T0, F>(mut func: F, p0: T0) -> bool
+        where
+            F: FnMut(T0) -> bool,
+        {
+            func(p0)
+        }
+
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        call_func(&mut self.func, p0)
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        access
+    }
+}
+
+impl<Func, T0, T1> Condition for FnSystem<fn(T0, T1) -> bool, Func, (T0::State, T1::State)>
+where
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1) -> bool + FnMut(T0::Item<'b>, T1::Item<'b>) -> bool,
+    T0: ReadOnlySystemArg,
+    T1: ReadOnlySystemArg,
+    Func: Send + Sync + 'static,
+{
+    fn evaluate(&mut self, world: &World) -> bool {
+        fn call_func<T0, T1, F>(mut func: F, p0: T0, p1: T1) -> bool
+        where
+            F: FnMut(T0, T1) -> bool,
+        {
+            func(p0, p1)
+        }
+
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        call_func(&mut self.func, p0, p1)
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        access
+    }
+}
+
+impl<Func, T0, T1, T2> Condition for FnSystem<fn(T0, T1, T2) -> bool, Func, (T0::State, T1::State, T2::State)>
+where
+    for<'a, 'b> &'a mut Func:
+        FnMut(T0, T1, T2) -> bool + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>) -> bool,
+    T0: ReadOnlySystemArg,
+    T1: ReadOnlySystemArg,
+    T2: ReadOnlySystemArg,
+    Func: Send + Sync + 'static,
+{
+    fn evaluate(&mut self, world: &World) -> bool {
+        fn call_func<T0, T1, T2, F>(mut func: F, p0: T0, p1: T1, p2: T2) -> bool
+        where
+            F: FnMut(T0, T1, T2) -> bool,
+        {
+            func(p0, p1, p2)
+        }
+
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        call_func(&mut self.func, p0, p1, p2)
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        access
+    }
+}
+
+impl<Func, T0, T1, T2, T3> Condition
+    for FnSystem<fn(T0, T1, T2, T3) -> bool, Func, (T0::State, T1::State, T2::State, T3::State)>
+where
+    for<'a, 'b> &'a mut Func:
+        FnMut(T0, T1, T2, T3) -> bool + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>, T3::Item<'b>) -> bool,
+    T0: ReadOnlySystemArg,
+    T1: ReadOnlySystemArg,
+    T2: ReadOnlySystemArg,
+    T3: ReadOnlySystemArg,
+    Func: Send + Sync + 'static,
+{
+    fn evaluate(&mut self, world: &World) -> bool {
+        fn call_func<T0, T1, T2, T3, F>(mut func: F, p0: T0, p1: T1, p2: T2, p3: T3) -> bool
+        where
+            F: FnMut(T0, T1, T2, T3) -> bool,
+        {
+            func(p0, p1, p2, p3)
+        }
+
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        call_func(&mut self.func, p0, p1, p2, p3)
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        access
+    }
+}
+
+impl<Func, T0, T1, T2, T3, T4> Condition
+    for FnSystem<
+        fn(T0, T1, T2, T3, T4) -> bool,
+        Func,
+        (T0::State, T1::State, T2::State, T3::State, T4::State),
+    >
+where
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4) -> bool
+        + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>, T3::Item<'b>, T4::Item<'b>) -> bool,
+    T0: ReadOnlySystemArg,
+    T1: ReadOnlySystemArg,
+    T2: ReadOnlySystemArg,
+    T3: ReadOnlySystemArg,
+    T4: ReadOnlySystemArg,
+    Func: Send + Sync + 'static,
+{
+    fn evaluate(&mut self, world: &World) -> bool {
+        fn call_func<T0, T1, T2, T3, T4, F>(mut func: F, p0: T0, p1: T1, p2: T2, p3: T3, p4: T4) -> bool
+        where
+            F: FnMut(T0, T1, T2, T3, T4) -> bool,
+        {
+            func(p0, p1, p2, p3, p4)
+        }
+
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        let p4 = T4::fetch(world, &mut self.state.4, ticks);
+        call_func(&mut self.func, p0, p1, p2, p3, p4)
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        T4::access(&mut access);
+        access
+    }
+}
+
+impl<Func, T0, T1, T2, T3, T4, T5> Condition
+    for FnSystem<
+        fn(T0, T1, T2, T3, T4, T5) -> bool,
+        Func,
+        (T0::State, T1::State, T2::State, T3::State, T4::State, T5::State),
+    >
+where
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5) -> bool
+        + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>, T3::Item<'b>, T4::Item<'b>, T5::Item<'b>) -> bool,
+    T0: ReadOnlySystemArg,
+    T1: ReadOnlySystemArg,
+    T2: ReadOnlySystemArg,
+    T3: ReadOnlySystemArg,
+    T4: ReadOnlySystemArg,
+    T5: ReadOnlySystemArg,
+    Func: Send + Sync + 'static,
+{
+    fn evaluate(&mut self, world: &World) -> bool {
+        fn call_func<T0, T1, T2, T3, T4, T5, F>(
+            mut func: F,
+            p0: T0,
+            p1: T1,
+            p2: T2,
+            p3: T3,
+            p4: T4,
+            p5: T5,
+        ) -> bool
+        where
+            F: FnMut(T0, T1, T2, T3, T4, T5) -> bool,
+        {
+            func(p0, p1, p2, p3, p4, p5)
+        }
+
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        let p4 = T4::fetch(world, &mut self.state.4, ticks);
+        let p5 = T5::fetch(world, &mut self.state.5, ticks);
+        call_func(&mut self.func, p0, p1, p2, p3, p4, p5)
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        T4::access(&mut access);
+        T5::access(&mut access);
+        access
+    }
+}
+
+impl<Func, T0, T1, T2, T3, T4, T5, T6> Condition
+    for FnSystem<
+        fn(T0, T1, T2, T3, T4, T5, T6) -> bool,
+        Func,
+        (T0::State, T1::State, T2::State, T3::State, T4::State, T5::State, T6::State),
+    >
+where
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6) -> bool
+        + FnMut(
+            T0::Item<'b>,
+            T1::Item<'b>,
+            T2::Item<'b>,
+            T3::Item<'b>,
+            T4::Item<'b>,
+            T5::Item<'b>,
+            T6::Item<'b>,
+        ) -> bool,
+    T0: ReadOnlySystemArg,
+    T1: ReadOnlySystemArg,
+    T2: ReadOnlySystemArg,
+    T3: ReadOnlySystemArg,
+    T4: ReadOnlySystemArg,
+    T5: ReadOnlySystemArg,
+    T6: ReadOnlySystemArg,
+    Func: Send + Sync + 'static,
+{
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate(&mut self, world: &World) -> bool {
+        fn call_func<T0, T1, T2, T3, T4, T5, T6, F>(
+            mut func: F,
+            p0: T0,
+            p1: T1,
+            p2: T2,
+            p3: T3,
+            p4: T4,
+            p5: T5,
+            p6: T6,
+        ) -> bool
+        where
+            F: FnMut(T0, T1, T2, T3, T4, T5, T6) -> bool,
+        {
+            func(p0, p1, p2, p3, p4, p5, p6)
+        }
+
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        let p4 = T4::fetch(world, &mut self.state.4, ticks);
+        let p5 = T5::fetch(world, &mut self.state.5, ticks);
+        let p6 = T6::fetch(world, &mut self.state.6, ticks);
+        call_func(&mut self.func, p0, p1, p2, p3, p4, p5, p6)
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        T4::access(&mut access);
+        T5::access(&mut access);
+        T6::access(&mut access);
+        access
+    }
+}
+
+impl<Func, T0, T1, T2, T3, T4, T5, T6, T7> Condition
+    for FnSystem<
+        fn(T0, T1, T2, T3, T4, T5, T6, T7) -> bool,
+        Func,
+        (T0::State, T1::State, T2::State, T3::State, T4::State, T5::State, T6::State, T7::State),
+    >
+where
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7) -> bool
+        + FnMut(
+            T0::Item<'b>,
+            T1::Item<'b>,
+            T2::Item<'b>,
+            T3::Item<'b>,
+            T4::Item<'b>,
+            T5::Item<'b>,
+            T6::Item<'b>,
+            T7::Item<'b>,
+        ) -> bool,
+    T0: ReadOnlySystemArg,
+    T1: ReadOnlySystemArg,
+    T2: ReadOnlySystemArg,
+    T3: ReadOnlySystemArg,
+    T4: ReadOnlySystemArg,
+    T5: ReadOnlySystemArg,
+    T6: ReadOnlySystemArg,
+    T7: ReadOnlySystemArg,
+    Func: Send + Sync + 'static,
+{
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate(&mut self, world: &World) -> bool {
+        fn call_func<T0, T1, T2, T3, T4, T5, T6, T7, F>(
+            mut func: F,
+            p0: T0,
+            p1: T1,
+            p2: T2,
+            p3: T3,
+            p4: T4,
+            p5: T5,
+            p6: T6,
+            p7: T7,
+        ) -> bool
+        where
+            F: FnMut(T0, T1, T2, T3, T4, T5, T6, T7) -> bool,
+        {
+            func(p0, p1, p2, p3, p4, p5, p6, p7)
+        }
+
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        let p4 = T4::fetch(world, &mut self.state.4, ticks);
+        let p5 = T5::fetch(world, &mut self.state.5, ticks);
+        let p6 = T6::fetch(world, &mut self.state.6, ticks);
+        let p7 = T7::fetch(world, &mut self.state.7, ticks);
+        call_func(&mut self.func, p0, p1, p2, p3, p4, p5, p6, p7)
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        T4::access(&mut access);
+        T5::access(&mut access);
+        T6::access(&mut access);
+        T7::access(&mut access);
+        access
+    }
+}
+
+impl<Func, T0, T1, T2, T3, T4, T5, T6, T7, T8> Condition
+    for FnSystem<
+        fn(T0, T1, T2, T3, T4, T5, T6, T7, T8) -> bool,
+        Func,
+        (T0::State, T1::State, T2::State, T3::State, T4::State, T5::State, T6::State, T7::State, T8::State),
+    >
+where
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8) -> bool
+        + FnMut(
+            T0::Item<'b>,
+            T1::Item<'b>,
+            T2::Item<'b>,
+            T3::Item<'b>,
+            T4::Item<'b>,
+            T5::Item<'b>,
+            T6::Item<'b>,
+            T7::Item<'b>,
+            T8::Item<'b>,
+        ) -> bool,
+    T0: ReadOnlySystemArg,
+    T1: ReadOnlySystemArg,
+    T2: ReadOnlySystemArg,
+    T3: ReadOnlySystemArg,
+    T4: ReadOnlySystemArg,
+    T5: ReadOnlySystemArg,
+    T6: ReadOnlySystemArg,
+    T7: ReadOnlySystemArg,
+    T8: ReadOnlySystemArg,
+    Func: Send + Sync + 'static,
 {
-    type System = FnSystem<((),), Self>;
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate(&mut self, world: &World) -> bool {
+        fn call_func<T0, T1, T2, T3, T4, T5, T6, T7, T8, F>(
+            mut func: F,
+            p0: T0,
+            p1: T1,
+            p2: T2,
+            p3: T3,
+            p4: T4,
+            p5: T5,
+            p6: T6,
+            p7: T7,
+            p8: T8,
+        ) -> bool
+        where
+            F: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8) -> bool,
+        {
+            func(p0, p1, p2, p3, p4, p5, p6, p7, p8)
+        }
+
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        let p4 = T4::fetch(world, &mut self.state.4, ticks);
+        let p5 = T5::fetch(world, &mut self.state.5, ticks);
+        let p6 = T6::fetch(world, &mut self.state.6, ticks);
+        let p7 = T7::fetch(world, &mut self.state.7, ticks);
+        let p8 = T8::fetch(world, &mut self.state.8, ticks);
+        call_func(&mut self.func, p0, p1, p2, p3, p4, p5, p6, p7, p8)
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        T4::access(&mut access);
+        T5::access(&mut access);
+        T6::access(&mut access);
+        T7::access(&mut access);
+        T8::access(&mut access);
+        access
+    }
+}
+
+impl<Func, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9> Condition
+    for FnSystem<
+        fn(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9) -> bool,
+        Func,
+        (
+            T0::State,
+            T1::State,
+            T2::State,
+            T3::State,
+            T4::State,
+            T5::State,
+            T6::State,
+            T7::State,
+            T8::State,
+            T9::State,
+        ),
+    >
+where
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9) -> bool
+        + FnMut(
+            T0::Item<'b>,
+            T1::Item<'b>,
+            T2::Item<'b>,
+            T3::Item<'b>,
+            T4::Item<'b>,
+            T5::Item<'b>,
+            T6::Item<'b>,
+            T7::Item<'b>,
+            T8::Item<'b>,
+            T9::Item<'b>,
+        ) -> bool,
+    T0: ReadOnlySystemArg,
+    T1: ReadOnlySystemArg,
+    T2: ReadOnlySystemArg,
+    T3: ReadOnlySystemArg,
+    T4: ReadOnlySystemArg,
+    T5: ReadOnlySystemArg,
+    T6: ReadOnlySystemArg,
+    T7: ReadOnlySystemArg,
+    T8: ReadOnlySystemArg,
+    T9: ReadOnlySystemArg,
+    Func: Send + Sync + 'static,
+{
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate(&mut self, world: &World) -> bool {
+        fn call_func<T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, F>(
+            mut func: F,
+            p0: T0,
+            p1: T1,
+            p2: T2,
+            p3: T3,
+            p4: T4,
+            p5: T5,
+            p6: T6,
+            p7: T7,
+            p8: T8,
+            p9: T9,
+        ) -> bool
+        where
+            F: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9) -> bool,
+        {
+            func(p0, p1, p2, p3, p4, p5, p6, p7, p8, p9)
+        }
+
+        let ticks = world.components.advance_ticks(&mut self.last_run);
+        let p0 = T0::fetch(world, &mut self.state.0, ticks);
+        let p1 = T1::fetch(world, &mut self.state.1, ticks);
+        let p2 = T2::fetch(world, &mut self.state.2, ticks);
+        let p3 = T3::fetch(world, &mut self.state.3, ticks);
+        let p4 = T4::fetch(world, &mut self.state.4, ticks);
+        let p5 = T5::fetch(world, &mut self.state.5, ticks);
+        let p6 = T6::fetch(world, &mut self.state.6, ticks);
+        let p7 = T7::fetch(world, &mut self.state.7, ticks);
+        let p8 = T8::fetch(world, &mut self.state.8, ticks);
+        let p9 = T9::fetch(world, &mut self.state.9, ticks);
+        call_func(&mut self.func, p0, p1, p2, p3, p4, p5, p6, p7, p8, p9)
+    }
+
+    fn name(&self) -> &'static str {
+        type_name::<Func>()
+    }
+
+    fn access(&self) -> Access {
+        let mut access = Access::default();
+        T0::access(&mut access);
+        T1::access(&mut access);
+        T2::access(&mut access);
+        T3::access(&mut access);
+        T4::access(&mut access);
+        T5::access(&mut access);
+        T6::access(&mut access);
+        T7::access(&mut access);
+        T8::access(&mut access);
+        T9::access(&mut access);
+        access
+    }
+}
+
+impl<Func, Out> SystemBuilder<fn() -> Out> for Func
+where
+    for<'a> &'a mut Func: FnMut() -> Out,
+    Func: FnMut() -> Out + Send + Sync,
+{
+    type System = FnSystem<fn() -> Out, Self, ()>;
 
     fn build_system(self) -> Self::System {
-        FnSystem { func: self, marker: PhantomData }
+        FnSystem { func: self, state: (), last_run: 0, marker: PhantomData }
     }
 }
 
-impl<Func, T0> SystemBuilder<(T0,)> for Func
+impl<Func, Out, T0> SystemBuilder<fn(T0) -> Out> for Func
 where
-    for<'a, 'b> &'a mut Func: FnMut(T0) + FnMut(T0::Item<'b>),
-    Func: FnMut(T0) + Send + Sync,
+    for<'a, 'b> &'a mut Func: FnMut(T0) -> Out + FnMut(T0::Item<'b>) -> Out,
+    Func: FnMut(T0) -> Out + Send + Sync,
     T0: SystemArg,
 {
-    type System = FnSystem<(T0,), Self>;
+    type System = FnSystem<fn(T0) -> Out, Self, (T0::State,)>;
 
     fn build_system(self) -> Self::System {
-        FnSystem { func: self, marker: PhantomData }
+        FnSystem {
+            func: self,
+            state: Default::default(),
+            last_run: 0,
+            marker: PhantomData,
+        }
     }
 }
 
-impl<Func, T0, T1> SystemBuilder<(T0, T1)> for Func
+impl<Func, Out, T0, T1> SystemBuilder<fn(T0, T1) -> Out> for Func
 where
-    for<'a, 'b> &'a mut Func: FnMut(T0, T1) + FnMut(T0::Item<'b>, T1::Item<'b>),
-    Func: FnMut(T0, T1) + Send + Sync,
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1) -> Out + FnMut(T0::Item<'b>, T1::Item<'b>) -> Out,
+    Func: FnMut(T0, T1) -> Out + Send + Sync,
     T0: SystemArg,
     T1: SystemArg,
 {
-    type System = FnSystem<(T0, T1), Self>;
+    type System = FnSystem<fn(T0, T1) -> Out, Self, (T0::State, T1::State)>;
 
     fn build_system(self) -> Self::System {
-        FnSystem { func: self, marker: PhantomData }
+        FnSystem {
+            func: self,
+            state: Default::default(),
+            last_run: 0,
+            marker: PhantomData,
+        }
     }
 }
 
-impl<Func, T0, T1, T2> SystemBuilder<(T0, T1, T2)> for Func
+impl<Func, Out, T0, T1, T2> SystemBuilder<fn(T0, T1, T2) -> Out> for Func
 where
-    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2) + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>),
-    Func: FnMut(T0, T1, T2) + Send + Sync,
+    for<'a, 'b> &'a mut Func:
+        FnMut(T0, T1, T2) -> Out + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>) -> Out,
+    Func: FnMut(T0, T1, T2) -> Out + Send + Sync,
     T0: SystemArg,
     T1: SystemArg,
     T2: SystemArg,
 {
-    type System = FnSystem<(T0, T1, T2), Self>;
+    type System = FnSystem<fn(T0, T1, T2) -> Out, Self, (T0::State, T1::State, T2::State)>;
 
     fn build_system(self) -> Self::System {
-        FnSystem { func: self, marker: PhantomData }
+        FnSystem {
+            func: self,
+            state: Default::default(),
+            last_run: 0,
+            marker: PhantomData,
+        }
     }
 }
 
-impl<Func, T0, T1, T2, T3> SystemBuilder<(T0, T1, T2, T3)> for Func
+impl<Func, Out, T0, T1, T2, T3> SystemBuilder<fn(T0, T1, T2, T3) -> Out> for Func
 where
     for<'a, 'b> &'a mut Func:
-        FnMut(T0, T1, T2, T3) + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>, T3::Item<'b>),
-    Func: FnMut(T0, T1, T2, T3) + Send + Sync,
+        FnMut(T0, T1, T2, T3) -> Out + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>, T3::Item<'b>) -> Out,
+    Func: FnMut(T0, T1, T2, T3) -> Out + Send + Sync,
     T0: SystemArg,
     T1: SystemArg,
     T2: SystemArg,
     T3: SystemArg,
 {
-    type System = FnSystem<(T0, T1, T2, T3), Self>;
+    type System = FnSystem<fn(T0, T1, T2, T3) -> Out, Self, (T0::State, T1::State, T2::State, T3::State)>;
 
     fn build_system(self) -> Self::System {
-        FnSystem { func: self, marker: PhantomData }
+        FnSystem {
+            func: self,
+            state: Default::default(),
+            last_run: 0,
+            marker: PhantomData,
+        }
     }
 }
 
-impl<Func, T0, T1, T2, T3, T4> SystemBuilder<(T0, T1, T2, T3, T4)> for Func
+impl<Func, Out, T0, T1, T2, T3, T4> SystemBuilder<fn(T0, T1, T2, T3, T4) -> Out> for Func
 where
-    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4)
-        + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>, T3::Item<'b>, T4::Item<'b>),
-    Func: FnMut(T0, T1, T2, T3, T4) + Send + Sync,
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4) -> Out
+        + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>, T3::Item<'b>, T4::Item<'b>) -> Out,
+    Func: FnMut(T0, T1, T2, T3, T4) -> Out + Send + Sync,
     T0: SystemArg,
     T1: SystemArg,
     T2: SystemArg,
     T3: SystemArg,
     T4: SystemArg,
 {
-    type System = FnSystem<(T0, T1, T2, T3, T4), Self>;
+    type System = FnSystem<
+        fn(T0, T1, T2, T3, T4) -> Out,
+        Self,
+        (T0::State, T1::State, T2::State, T3::State, T4::State),
+    >;
 
     fn build_system(self) -> Self::System {
-        FnSystem { func: self, marker: PhantomData }
+        FnSystem {
+            func: self,
+            state: Default::default(),
+            last_run: 0,
+            marker: PhantomData,
+        }
     }
 }
 
-impl<Func, T0, T1, T2, T3, T4, T5> SystemBuilder<(T0, T1, T2, T3, T4, T5)> for Func
+impl<Func, Out, T0, T1, T2, T3, T4, T5> SystemBuilder<fn(T0, T1, T2, T3, T4, T5) -> Out> for Func
 where
-    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5)
-        + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>, T3::Item<'b>, T4::Item<'b>, T5::Item<'b>),
-    Func: FnMut(T0, T1, T2, T3, T4, T5) + Send + Sync,
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5) -> Out
+        + FnMut(T0::Item<'b>, T1::Item<'b>, T2::Item<'b>, T3::Item<'b>, T4::Item<'b>, T5::Item<'b>) -> Out,
+    Func: FnMut(T0, T1, T2, T3, T4, T5) -> Out + Send + Sync,
     T0: SystemArg,
     T1: SystemArg,
     T2: SystemArg,
@@ -505,16 +1673,25 @@ where
     T4: SystemArg,
     T5: SystemArg,
 {
-    type System = FnSystem<(T0, T1, T2, T3, T4, T5), Self>;
+    type System = FnSystem<
+        fn(T0, T1, T2, T3, T4, T5) -> Out,
+        Self,
+        (T0::State, T1::State, T2::State, T3::State, T4::State, T5::State),
+    >;
 
     fn build_system(self) -> Self::System {
-        FnSystem { func: self, marker: PhantomData }
+        FnSystem {
+            func: self,
+            state: Default::default(),
+            last_run: 0,
+            marker: PhantomData,
+        }
     }
 }
 
-impl<Func, T0, T1, T2, T3, T4, T5, T6> SystemBuilder<(T0, T1, T2, T3, T4, T5, T6)> for Func
+impl<Func, Out, T0, T1, T2, T3, T4, T5, T6> SystemBuilder<fn(T0, T1, T2, T3, T4, T5, T6) -> Out> for Func
 where
-    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6)
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6) -> Out
         + FnMut(
             T0::Item<'b>,
             T1::Item<'b>,
@@ -523,8 +1700,8 @@ where
             T4::Item<'b>,
             T5::Item<'b>,
             T6::Item<'b>,
-        ),
-    Func: FnMut(T0, T1, T2, T3, T4, T5, T6) + Send + Sync,
+        ) -> Out,
+    Func: FnMut(T0, T1, T2, T3, T4, T5, T6) -> Out + Send + Sync,
     T0: SystemArg,
     T1: SystemArg,
     T2: SystemArg,
@@ -533,16 +1710,26 @@ where
     T5: SystemArg,
     T6: SystemArg,
 {
-    type System = FnSystem<(T0, T1, T2, T3, T4, T5, T6), Self>;
+    type System = FnSystem<
+        fn(T0, T1, T2, T3, T4, T5, T6) -> Out,
+        Self,
+        (T0::State, T1::State, T2::State, T3::State, T4::State, T5::State, T6::State),
+    >;
 
     fn build_system(self) -> Self::System {
-        FnSystem { func: self, marker: PhantomData }
+        FnSystem {
+            func: self,
+            state: Default::default(),
+            last_run: 0,
+            marker: PhantomData,
+        }
     }
 }
 
-impl<Func, T0, T1, T2, T3, T4, T5, T6, T7> SystemBuilder<(T0, T1, T2, T3, T4, T5, T6, T7)> for Func
+impl<Func, Out, T0, T1, T2, T3, T4, T5, T6, T7> SystemBuilder<fn(T0, T1, T2, T3, T4, T5, T6, T7) -> Out>
+    for Func
 where
-    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7)
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7) -> Out
         + FnMut(
             T0::Item<'b>,
             T1::Item<'b>,
@@ -552,8 +1739,8 @@ where
             T5::Item<'b>,
             T6::Item<'b>,
             T7::Item<'b>,
-        ),
-    Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7) + Send + Sync,
+        ) -> Out,
+    Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7) -> Out + Send + Sync,
     T0: SystemArg,
     T1: SystemArg,
     T2: SystemArg,
@@ -563,16 +1750,26 @@ where
     T6: SystemArg,
     T7: SystemArg,
 {
-    type System = FnSystem<(T0, T1, T2, T3, T4, T5, T6, T7), Self>;
+    type System = FnSystem<
+        fn(T0, T1, T2, T3, T4, T5, T6, T7) -> Out,
+        Self,
+        (T0::State, T1::State, T2::State, T3::State, T4::State, T5::State, T6::State, T7::State),
+    >;
 
     fn build_system(self) -> Self::System {
-        FnSystem { func: self, marker: PhantomData }
+        FnSystem {
+            func: self,
+            state: Default::default(),
+            last_run: 0,
+            marker: PhantomData,
+        }
     }
 }
 
-impl<Func, T0, T1, T2, T3, T4, T5, T6, T7, T8> SystemBuilder<(T0, T1, T2, T3, T4, T5, T6, T7, T8)> for Func
+impl<Func, Out, T0, T1, T2, T3, T4, T5, T6, T7, T8>
+    SystemBuilder<fn(T0, T1, T2, T3, T4, T5, T6, T7, T8) -> Out> for Func
 where
-    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8)
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8) -> Out
         + FnMut(
             T0::Item<'b>,
             T1::Item<'b>,
@@ -583,8 +1780,8 @@ where
             T6::Item<'b>,
             T7::Item<'b>,
             T8::Item<'b>,
-        ),
-    Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8) + Send + Sync,
+        ) -> Out,
+    Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8) -> Out + Send + Sync,
     T0: SystemArg,
     T1: SystemArg,
     T2: SystemArg,
@@ -595,17 +1792,26 @@ where
     T7: SystemArg,
     T8: SystemArg,
 {
-    type System = FnSystem<(T0, T1, T2, T3, T4, T5, T6, T7, T8), Self>;
+    type System = FnSystem<
+        fn(T0, T1, T2, T3, T4, T5, T6, T7, T8) -> Out,
+        Self,
+        (T0::State, T1::State, T2::State, T3::State, T4::State, T5::State, T6::State, T7::State, T8::State),
+    >;
 
     fn build_system(self) -> Self::System {
-        FnSystem { func: self, marker: PhantomData }
+        FnSystem {
+            func: self,
+            state: Default::default(),
+            last_run: 0,
+            marker: PhantomData,
+        }
     }
 }
 
-impl<Func, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9> SystemBuilder<(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9)>
-    for Func
+impl<Func, Out, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9>
+    SystemBuilder<fn(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9) -> Out> for Func
 where
-    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9)
+    for<'a, 'b> &'a mut Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9) -> Out
         + FnMut(
             T0::Item<'b>,
             T1::Item<'b>,
@@ -617,8 +1823,8 @@ where
             T7::Item<'b>,
             T8::Item<'b>,
             T9::Item<'b>,
-        ),
-    Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9) + Send + Sync,
+        ) -> Out,
+    Func: FnMut(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9) -> Out + Send + Sync,
     T0: SystemArg,
     T1: SystemArg,
     T2: SystemArg,
@@ -630,9 +1836,29 @@ where
     T8: SystemArg,
     T9: SystemArg,
 {
-    type System = FnSystem<(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9), Self>;
+    type System = FnSystem<
+        fn(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9) -> Out,
+        Self,
+        (
+            T0::State,
+            T1::State,
+            T2::State,
+            T3::State,
+            T4::State,
+            T5::State,
+            T6::State,
+            T7::State,
+            T8::State,
+            T9::State,
+        ),
+    >;
 
     fn build_system(self) -> Self::System {
-        FnSystem { func: self, marker: PhantomData }
+        FnSystem {
+            func: self,
+            state: Default::default(),
+            last_run: 0,
+            marker: PhantomData,
+        }
     }
 }
@@ -0,0 +1,165 @@
+use std::{mem, time::Duration};
+
+use crate::Resource;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Clock {
+    #[default]
+    Real,
+    Manual(Duration),
+}
+
+#[derive(Debug, Clone)]
+pub struct Time {
+    delta: Duration,
+    elapsed: Duration,
+    frame: u64,
+    paused: bool,
+    scale: f64,
+}
+
+impl Resource for Time {}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self {
+            delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            frame: 0,
+            paused: false,
+            scale: 1.0,
+        }
+    }
+}
+
+impl Time {
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_secs(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn elapsed_secs(&self) -> f64 {
+        self.elapsed.as_secs_f64()
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    pub fn set_scale(&mut self, scale: f64) {
+        assert!(
+            scale >= 0.0 && scale.is_finite(),
+            "time scale must be finite and non-negative, got {}",
+            scale
+        );
+        self.scale = scale;
+    }
+
+    pub(crate) fn update(&mut self, raw: Duration) {
+        self.frame += 1;
+        self.delta = match self.paused {
+            | true => Duration::ZERO,
+            | false if self.scale == 1.0 => raw,
+            | false => raw.mul_f64(self.scale),
+        };
+        self.elapsed += self.delta;
+    }
+
+    pub(crate) fn set_delta(&mut self, delta: Duration) -> Duration {
+        mem::replace(&mut self.delta, delta)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FixedTime {
+    step: Duration,
+    max_steps: u32,
+    accumulator: Duration,
+}
+
+impl Resource for FixedTime {}
+
+impl Default for FixedTime {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1) / 60)
+    }
+}
+
+impl FixedTime {
+    pub fn new(step: Duration) -> Self {
+        assert!(!step.is_zero(), "fixed timestep must be non-zero");
+        Self { step, max_steps: 8, accumulator: Duration::ZERO }
+    }
+
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    pub fn step_secs(&self) -> f32 {
+        self.step.as_secs_f32()
+    }
+
+    pub fn set_step(&mut self, step: Duration) {
+        *self = Self {
+            max_steps: self.max_steps,
+            accumulator: self.accumulator,
+            ..Self::new(step)
+        };
+    }
+
+    pub fn max_steps(&self) -> u32 {
+        self.max_steps
+    }
+
+    /// Caps how many fixed steps a single frame may run. Time beyond the cap is dropped, so a
+    /// long stall slows the simulation down instead of running ever more steps to catch up.
+    pub fn set_max_steps(&mut self, max_steps: u32) {
+        assert!(max_steps > 0, "fixed timestep must allow at least one step per frame");
+        self.max_steps = max_steps;
+    }
+
+    pub fn accumulated(&self) -> Duration {
+        self.accumulator
+    }
+
+    pub fn overstep_fraction(&self) -> f64 {
+        self.accumulator.as_secs_f64() / self.step.as_secs_f64()
+    }
+
+    pub(crate) fn accumulate(&mut self, delta: Duration) {
+        self.accumulator = (self.accumulator + delta).min(self.step * self.max_steps);
+    }
+
+    pub(crate) fn expend(&mut self) -> bool {
+        match self.accumulator.checked_sub(self.step) {
+            | Some(rest) => {
+                self.accumulator = rest;
+                true
+            }
+            | None => false,
+        }
+    }
+}